@@ -4,25 +4,37 @@
 #![cfg_attr(feature = "no_console", windows_subsystem = "windows")]
 
 use anyhow::{bail, Context};
+use futures_util::StreamExt;
 use tracing::info;
 use wm::{
   cleanup::run_cleanup, common::platform::NativeWindow,
-  containers::ContainerDto, ipc_client::IpcClient,
-  ipc_server::ClientResponseData, windows::WindowDto, wm_event::WmEvent,
+  user_config::UserConfig,
+};
+use wm_common::{ContainerDto, IpcArgs, SubscribableEvent, WmEvent};
+use wm_ipc_client::{
+  ConnectionClosed, EventsMissed, IpcClient, IpcClientOptions,
 };
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
   tracing_subscriber::fmt().init();
 
-  let mut client = IpcClient::connect().await?;
+  // The WM passes its IPC address to the watcher via environment
+  // variables, which take precedence over the config file.
+  let ipc_config = UserConfig::client_ipc_config(&IpcArgs::default())?;
+
+  // Reconnection is disabled, since a lost connection means that the WM
+  // has exited and cleanup should run right away.
+  let options = IpcClientOptions {
+    reconnect_attempts: 0,
+    ..Default::default()
+  };
+
+  let mut client =
+    IpcClient::connect_with_options(&ipc_config, options).await?;
 
   // Get handles to windows that are already open on watcher launch.
-  let mut managed_handles = query_initial_windows(&mut client)
-    .await?
-    .into_iter()
-    .map(|window| window.handle)
-    .collect::<Vec<_>>();
+  let mut managed_handles = query_managed_handles(&mut client).await?;
 
   // Update window handles on window manage/unmanage events.
   let subscribe_res =
@@ -45,85 +57,66 @@ async fn main() -> anyhow::Result<()> {
   Ok(())
 }
 
-async fn query_initial_windows(
+async fn query_managed_handles(
   client: &mut IpcClient,
-) -> anyhow::Result<Vec<WindowDto>> {
-  let query_message = "query windows";
-
-  client
-    .send(query_message)
+) -> anyhow::Result<Vec<isize>> {
+  let windows = client
+    .query_windows()
     .await
-    .context("Failed to send window query command.")?;
+    .context("Failed to query windows from IPC server.")?;
 
-  client
-    .client_response(query_message)
-    .await
-    .and_then(|response| match response.data {
-      Some(ClientResponseData::Windows(data)) => Some(data),
-      _ => None,
-    })
-    .map(|data| {
-      data
-        .windows
-        .into_iter()
-        .filter_map(|container| match container {
-          ContainerDto::Window(window) => Some(window),
-          _ => None,
-        })
-        .collect::<Vec<_>>()
-    })
-    .context("Invalid data in windows query response.")
+  Ok(windows.into_iter().map(|window| window.handle).collect())
 }
 
 async fn watch_managed_handles(
   client: &mut IpcClient,
   handles: &mut Vec<isize>,
 ) -> anyhow::Result<()> {
-  let subscription_message =
-    "sub -e window_managed window_unmanaged application_exiting";
-
-  client
-    .send(subscription_message)
+  let mut events = client
+    .subscribe(&[
+      SubscribableEvent::WindowManaged,
+      SubscribableEvent::WindowUnmanaged,
+      SubscribableEvent::ApplicationExiting,
+    ])
     .await
-    .context("Failed to send subscribe command to IPC server.")?;
+    .context("Failed to subscribe to events from IPC server.")?;
 
-  let subscription_id = client
-    .client_response(subscription_message)
-    .await
-    .and_then(|response| match response.data {
-      Some(ClientResponseData::EventSubscribe(data)) => {
-        Some(data.subscription_id)
-      }
-      _ => None,
-    })
-    .context("No subscription ID in watcher event subscription.")?;
-
-  loop {
-    let event_data = client
-      .event_subscription(&subscription_id)
-      .await
-      .and_then(|event| event.data);
-
-    match event_data {
-      Some(WmEvent::WindowManaged { managed_window }) => {
+  while let Some(event) = events.next().await {
+    match event {
+      Ok(WmEvent::WindowManaged { managed_window }) => {
         if let ContainerDto::Window(window) = managed_window {
           info!("Watcher added handle: {}.", window.handle);
           handles.push(window.handle);
         }
       }
-      Some(WmEvent::WindowUnmanaged {
+      Ok(WmEvent::WindowUnmanaged {
         unmanaged_handle, ..
       }) => {
         info!("Watcher removed handle: {}.", unmanaged_handle);
         handles.retain(|&handle| handle != unmanaged_handle);
       }
-      Some(WmEvent::ApplicationExiting) => {
+      Ok(WmEvent::ApplicationExiting) => {
+        return Ok(());
+      }
+      Ok(_) => unreachable!(),
+      // Re-sync the managed handles if events were dropped.
+      Err(err) if err.is::<EventsMissed>() => {
+        info!("Watcher missed events. Re-syncing handles.");
+        *handles = query_managed_handles(client).await?;
+      }
+      // The WM closes connections gracefully when shutting down.
+      Err(err)
+        if err
+          .downcast_ref::<ConnectionClosed>()
+          .is_some_and(ConnectionClosed::is_shutdown) =>
+      {
         return Ok(());
       }
-      Some(_) => unreachable!(),
-      None => {
-        bail!("IPC connection closed unexpectedly.")
+      Err(err) => {
+        return Err(err).context("IPC connection closed unexpectedly.");
       }
     }
   }
+
+  bail!("IPC connection closed unexpectedly.")
 }
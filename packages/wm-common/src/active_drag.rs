@@ -1,12 +1,15 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema, Serialize)]
 pub struct ActiveDrag {
   pub operation: Option<ActiveDragOperation>,
   pub is_from_tiling: bool,
 }
 
-#[derive(Debug, Copy, Clone, Deserialize, PartialEq, Serialize)]
+#[derive(
+  Debug, Copy, Clone, Deserialize, JsonSchema, PartialEq, Serialize,
+)]
 pub enum ActiveDragOperation {
   Moving,
   Resizing,
@@ -1,6 +1,7 @@
-use std::str::FromStr;
+use std::{borrow::Cow, str::FromStr};
 
 use anyhow::bail;
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
 use serde::{Deserialize, Deserializer, Serialize};
 
 #[derive(Debug, Clone, Serialize)]
@@ -65,3 +66,33 @@ impl<'de> Deserialize<'de> for Color {
     }
   }
 }
+
+/// Schema for either a hex string (e.g. `#8dbcff`) or an RGBA struct,
+/// matching the accepted formats in `Deserialize`.
+impl JsonSchema for Color {
+  fn schema_name() -> Cow<'static, str> {
+    "Color".into()
+  }
+
+  fn json_schema(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+      "anyOf": [
+        {
+          "type": "string",
+          "pattern": "^#([0-9a-fA-F]{6}|[0-9a-fA-F]{8})$",
+          "description": "Hex color with optional alpha (e.g. `#8dbcff80`)."
+        },
+        {
+          "type": "object",
+          "properties": {
+            "r": { "type": "integer", "minimum": 0, "maximum": 255 },
+            "g": { "type": "integer", "minimum": 0, "maximum": 255 },
+            "b": { "type": "integer", "minimum": 0, "maximum": 255 },
+            "a": { "type": "integer", "minimum": 0, "maximum": 255 }
+          },
+          "required": ["r", "g", "b", "a"]
+        }
+      ]
+    })
+  }
+}
@@ -0,0 +1,527 @@
+use std::{borrow::Cow, fmt, iter::Peekable, str::Chars};
+
+use clap::error::{ContextKind, ContextValue};
+use serde::Serialize;
+
+/// Argument of a command string, along with its position in the string.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandArg {
+  /// Argument with quotes and escapes removed.
+  pub value: String,
+
+  /// Character offset of the argument within the command string.
+  pub position: usize,
+
+  /// Number of characters that the argument spans in the command
+  /// string, including any quotes.
+  pub length: usize,
+}
+
+/// Operator that joins two chained commands.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainOperator {
+  /// `;` - The next command is run regardless of whether the previous
+  /// command succeeded.
+  Sequence,
+
+  /// `&&` - The next command is only run if the previous command
+  /// succeeded.
+  And,
+}
+
+/// Single command within a chain of commands.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandSegment {
+  pub args: Vec<CommandArg>,
+
+  /// Operator that joins the command to the previous one. `None` for
+  /// the first command.
+  pub operator: Option<ChainOperator>,
+}
+
+/// Error for a malformed command string. Displays the command string
+/// with the offending part underlined.
+#[derive(Clone, Debug)]
+pub struct CommandSyntaxError {
+  pub message: String,
+  pub input: String,
+  pub position: usize,
+  pub length: usize,
+}
+
+impl CommandSyntaxError {
+  pub fn new(
+    message: impl Into<String>,
+    input: &str,
+    position: usize,
+    length: usize,
+  ) -> Self {
+    Self {
+      message: message.into(),
+      input: input.to_string(),
+      position,
+      length: length.max(1),
+    }
+  }
+
+  /// Creates an error that points to the given argument.
+  pub fn at_arg(
+    message: impl Into<String>,
+    input: &str,
+    arg: &CommandArg,
+  ) -> Self {
+    Self::new(message, input, arg.position, arg.length)
+  }
+}
+
+impl fmt::Display for CommandSyntaxError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}\n\n  {}\n  {}{}",
+      self.message,
+      self.input,
+      " ".repeat(self.position),
+      "^".repeat(self.length)
+    )
+  }
+}
+
+impl std::error::Error for CommandSyntaxError {}
+
+/// Splits a command string into commands chained with `;` or `&&` (e.g.
+/// `focus --workspace 1 && move --workspace 2`).
+///
+/// Arguments are separated by whitespace. Single quotes keep their
+/// contents as is, whereas double quotes allow `\"` to escape a quote.
+/// Outside of quotes, a backslash escapes a quote, whitespace, `;` or
+/// `&`. Other backslashes are kept, so that Windows paths don't need to
+/// be escaped.
+pub fn parse_command_chain(
+  input: &str,
+) -> Result<Vec<CommandSegment>, CommandSyntaxError> {
+  let mut tokenizer = Tokenizer {
+    input,
+    chars: input.chars().peekable(),
+    position: 0,
+  };
+
+  let mut segments = Vec::new();
+  let mut args = Vec::new();
+  let mut operator = None;
+
+  while let Some(token) = tokenizer.next_token()? {
+    match token {
+      Token::Arg(arg) => args.push(arg),
+      Token::Operator(next_operator, position) => {
+        if args.is_empty() {
+          return Err(CommandSyntaxError::new(
+            format!(
+              "Expected a command before `{}`.",
+              operator_str(next_operator)
+            ),
+            input,
+            position,
+            operator_str(next_operator).len(),
+          ));
+        }
+
+        segments.push(CommandSegment {
+          args: std::mem::take(&mut args),
+          operator,
+        });
+
+        operator = Some(next_operator);
+      }
+    }
+  }
+
+  match (args.is_empty(), operator) {
+    (false, _) => segments.push(CommandSegment { args, operator }),
+    // A trailing `;` is allowed, same as in shells.
+    (true, Some(ChainOperator::Sequence)) => {}
+    (true, Some(ChainOperator::And)) => {
+      return Err(CommandSyntaxError::new(
+        "Expected a command after `&&`.",
+        input,
+        input.chars().count(),
+        1,
+      ));
+    }
+    (true, None) => {
+      return Err(CommandSyntaxError::new(
+        "Expected a command.",
+        input,
+        0,
+        1,
+      ));
+    }
+  }
+
+  Ok(segments)
+}
+
+/// Splits a single command string into its arguments. Errors if the
+/// string contains chained commands.
+pub fn parse_command_args(
+  input: &str,
+) -> Result<Vec<CommandArg>, CommandSyntaxError> {
+  let mut segments = parse_command_chain(input)?.into_iter();
+
+  // Safety: Parsing fails if there are no commands.
+  let segment = segments.next().unwrap();
+
+  match segments.next() {
+    None => Ok(segment.args),
+    Some(next_segment) => {
+      let operator = next_segment.operator.unwrap_or(ChainOperator::And);
+
+      // The operator is the first non-whitespace character after the
+      // last argument of the first command.
+      let end = segment
+        .args
+        .last()
+        .map_or(0, |arg| arg.position + arg.length);
+
+      let position = end
+        + input
+          .chars()
+          .skip(end)
+          .take_while(|char| char.is_whitespace())
+          .count();
+
+      Err(CommandSyntaxError::new(
+        "Chaining commands with `;` or `&&` isn't supported here.",
+        input,
+        position,
+        operator_str(operator).len(),
+      ))
+    }
+  }
+}
+
+/// Quotes an argument if needed, so that it's parsed back as a single
+/// argument.
+///
+/// Arguments with a backslash or `&` are always quoted, since they could
+/// otherwise escape or form an operator with an adjacent character.
+pub fn quote_command_arg(arg: &str) -> Cow<'_, str> {
+  let needs_quotes = arg.is_empty()
+    || arg.chars().any(|char| {
+      char.is_whitespace() || matches!(char, '\'' | '"' | ';' | '&' | '\\')
+    });
+
+  match needs_quotes {
+    // Single quotes can't be escaped within single quotes, so they're
+    // escaped between two quoted parts instead (e.g. `'it'\''s'`).
+    true => Cow::Owned(format!("'{}'", arg.replace('\'', r"'\''"))),
+    false => Cow::Borrowed(arg),
+  }
+}
+
+/// Joins command line arguments into a command string, quoting them
+/// where needed.
+///
+/// Arguments that are exactly `;` or `&&` are kept as chain operators.
+pub fn join_command_args<T: AsRef<str>>(args: &[T]) -> String {
+  args
+    .iter()
+    .map(|arg| match arg.as_ref() {
+      ";" | "&&" => Cow::Borrowed(arg.as_ref()),
+      arg => quote_command_arg(arg),
+    })
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Finds the argument that caused a clap parsing error (e.g. an unknown
+/// flag or an invalid value).
+pub fn offending_arg<'a>(
+  err: &clap::Error,
+  args: &'a [CommandArg],
+) -> Option<&'a CommandArg> {
+  [
+    ContextKind::InvalidValue,
+    ContextKind::InvalidSubcommand,
+    ContextKind::InvalidArg,
+  ]
+  .into_iter()
+  .find_map(|kind| match err.get(kind) {
+    Some(ContextValue::String(value)) => Some((kind, value.as_str())),
+    _ => None,
+  })
+  .and_then(|(kind, value)| {
+    // Flags are given with their value name (e.g. `--workspace
+    // <WORKSPACE>`).
+    let name = value.split_whitespace().next().unwrap_or(value);
+
+    args.iter().find(|arg| match kind {
+      ContextKind::InvalidValue => {
+        arg.value == value || arg.value.ends_with(&format!("={}", value))
+      }
+      _ => {
+        arg.value == name || arg.value.starts_with(&format!("{}=", name))
+      }
+    })
+  })
+}
+
+fn operator_str(operator: ChainOperator) -> &'static str {
+  match operator {
+    ChainOperator::Sequence => ";",
+    ChainOperator::And => "&&",
+  }
+}
+
+enum Token {
+  Arg(CommandArg),
+  Operator(ChainOperator, usize),
+}
+
+struct Tokenizer<'a> {
+  input: &'a str,
+  chars: Peekable<Chars<'a>>,
+
+  /// Character offset of the next character.
+  position: usize,
+}
+
+impl Tokenizer<'_> {
+  fn next_char(&mut self) -> Option<char> {
+    let char = self.chars.next()?;
+    self.position += 1;
+    Some(char)
+  }
+
+  /// Peeks at the character after the next one.
+  fn peek_second(&self) -> Option<char> {
+    let mut chars = self.chars.clone();
+    chars.next();
+    chars.next()
+  }
+
+  fn next_token(&mut self) -> Result<Option<Token>, CommandSyntaxError> {
+    while self.chars.peek().is_some_and(|char| char.is_whitespace()) {
+      self.next_char();
+    }
+
+    let start = self.position;
+
+    match self.chars.peek().copied() {
+      None => return Ok(None),
+      Some(';') => {
+        self.next_char();
+        return Ok(Some(Token::Operator(ChainOperator::Sequence, start)));
+      }
+      Some('&') if self.peek_second() == Some('&') => {
+        self.next_char();
+        self.next_char();
+        return Ok(Some(Token::Operator(ChainOperator::And, start)));
+      }
+      _ => {}
+    }
+
+    let mut value = String::new();
+
+    while let Some(&char) = self.chars.peek() {
+      match char {
+        char if char.is_whitespace() => break,
+        ';' => break,
+        '&' if self.peek_second() == Some('&') => break,
+        '\'' => {
+          let quote_position = self.position;
+          self.next_char();
+
+          loop {
+            match self.next_char() {
+              Some('\'') => break,
+              Some(char) => value.push(char),
+              None => {
+                return Err(self.unterminated_quote(quote_position));
+              }
+            }
+          }
+        }
+        '"' => {
+          let quote_position = self.position;
+          self.next_char();
+
+          loop {
+            match self.next_char() {
+              Some('"') => break,
+              Some('\\') if self.chars.peek() == Some(&'"') => {
+                value.push('"');
+                self.next_char();
+              }
+              Some(char) => value.push(char),
+              None => {
+                return Err(self.unterminated_quote(quote_position));
+              }
+            }
+          }
+        }
+        '\\' => {
+          self.next_char();
+
+          match self.chars.peek() {
+            Some(&next) if is_escapable(next) => {
+              value.push(next);
+              self.next_char();
+            }
+            _ => value.push('\\'),
+          }
+        }
+        char => {
+          value.push(char);
+          self.next_char();
+        }
+      }
+    }
+
+    Ok(Some(Token::Arg(CommandArg {
+      value,
+      position: start,
+      length: self.position - start,
+    })))
+  }
+
+  fn unterminated_quote(&self, position: usize) -> CommandSyntaxError {
+    CommandSyntaxError::new(
+      "Missing closing quote.",
+      self.input,
+      position,
+      self.position - position,
+    )
+  }
+}
+
+/// Whether a backslash outside of quotes escapes the given character.
+fn is_escapable(char: char) -> bool {
+  char.is_whitespace() || matches!(char, '\'' | '"' | ';' | '&')
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Parses the input into the argument values of each command.
+  fn parse_values(input: &str) -> Vec<Vec<String>> {
+    parse_command_chain(input)
+      .unwrap()
+      .into_iter()
+      .map(|segment| {
+        segment.args.into_iter().map(|arg| arg.value).collect()
+      })
+      .collect()
+  }
+
+  /// Parses the input and returns the error's message, position and
+  /// length.
+  fn parse_error(input: &str) -> (String, usize, usize) {
+    let err = parse_command_chain(input).unwrap_err();
+    (err.message, err.position, err.length)
+  }
+
+  #[test]
+  fn splits_on_whitespace() {
+    assert_eq!(
+      parse_values("  focus   --workspace 1 "),
+      [["focus", "--workspace", "1"]]
+    );
+  }
+
+  #[test]
+  fn keeps_quoted_args_together() {
+    assert_eq!(
+      parse_values(r#"shell-exec 'a "b" c' "it's \"d\"" 'x'y"#),
+      [["shell-exec", r#"a "b" c"#, r#"it's "d""#, "xy"]]
+    );
+
+    assert_eq!(parse_values("a '' \"\""), [["a", "", ""]]);
+  }
+
+  #[test]
+  fn handles_escapes() {
+    assert_eq!(
+      parse_values(r#"a b\ c \'d \"e\" \; f\&\&"#),
+      [["a", "b c", "'d", "\"e\"", ";", "f&&"]]
+    );
+
+    // Other backslashes are kept, including within quotes.
+    assert_eq!(
+      parse_values(r#"a C:\Users\me 'C:\a b\' "\\x\"""#),
+      [["a", r"C:\Users\me", r"C:\a b\", r#"\\x""#]]
+    );
+  }
+
+  #[test]
+  fn splits_chained_commands() {
+    let segments = parse_command_chain("a 1; b && c;").unwrap();
+
+    assert_eq!(
+      segments
+        .iter()
+        .map(|segment| segment.operator)
+        .collect::<Vec<_>>(),
+      [
+        None,
+        Some(ChainOperator::Sequence),
+        Some(ChainOperator::And)
+      ]
+    );
+
+    assert_eq!(parse_values("a;b&&c"), [["a"], ["b"], ["c"]]);
+    assert_eq!(
+      parse_values("a 'b;c' 'd&&e' f&g"),
+      [["a", "b;c", "d&&e", "f&g"]]
+    );
+  }
+
+  #[test]
+  fn records_arg_positions() {
+    let segments = parse_command_chain("ab 'c d' && e").unwrap();
+
+    let positions = segments
+      .iter()
+      .flat_map(|segment| &segment.args)
+      .map(|arg| (arg.position, arg.length))
+      .collect::<Vec<_>>();
+
+    assert_eq!(positions, [(0, 2), (3, 5), (12, 1)]);
+  }
+
+  #[test]
+  fn points_errors_to_offending_part() {
+    assert_eq!(
+      parse_error("a 'b c"),
+      ("Missing closing quote.".to_string(), 2, 4)
+    );
+    assert_eq!(
+      parse_error("a; && b"),
+      ("Expected a command before `&&`.".to_string(), 3, 2)
+    );
+    assert_eq!(
+      parse_error("a &&"),
+      ("Expected a command after `&&`.".to_string(), 4, 1)
+    );
+    assert_eq!(
+      parse_error("  "),
+      ("Expected a command.".to_string(), 0, 1)
+    );
+
+    let err = parse_command_args("a b ; c").unwrap_err();
+    assert_eq!((err.position, err.length), (4, 1));
+  }
+
+  #[test]
+  fn quoted_args_parse_back() {
+    for arg in ["", "a b", "it's", "a;b", "a&&b", r"C:\a b\", "\"x\""] {
+      let command = join_command_args(&["a", arg]);
+
+      assert_eq!(parse_values(&command), [["a", arg]], "{}", command);
+    }
+
+    assert_eq!(join_command_args(&["a", ";", "b"]), "a ; b");
+  }
+}
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use anyhow::bail;
 use serde::Serialize;
@@ -17,7 +17,7 @@ impl Direction {
   ///
   /// Example:
   /// ```
-  /// # use wm::common::Direction;
+  /// # use wm_common::Direction;
   /// let dir = Direction::Left.inverse();
   /// assert_eq!(dir, Direction::Right);
   /// ```
@@ -38,7 +38,7 @@ impl FromStr for Direction {
   ///
   /// Example:
   /// ```
-  /// # use wm::common::Direction;
+  /// # use wm_common::Direction;
   /// # use std::str::FromStr;
   /// let dir = Direction::from_str("left");
   /// assert_eq!(dir.unwrap(), Direction::Left);
@@ -53,3 +53,14 @@ impl FromStr for Direction {
     }
   }
 }
+
+impl fmt::Display for Direction {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Direction::Left => write!(f, "left"),
+      Direction::Right => write!(f, "right"),
+      Direction::Up => write!(f, "up"),
+      Direction::Down => write!(f, "down"),
+    }
+  }
+}
@@ -0,0 +1,112 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+  MonitorDto, RootContainerDto, SplitContainerDto, WindowDto, WorkspaceDto,
+};
+
+/// User-friendly representation of a container.
+///
+/// Used for IPC and debug logging.
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContainerDto {
+  Root(RootContainerDto),
+  Monitor(MonitorDto),
+  Workspace(WorkspaceDto),
+  Split(SplitContainerDto),
+  Window(WindowDto),
+}
+
+impl ContainerDto {
+  /// ID of the container.
+  pub fn id(&self) -> Uuid {
+    match self {
+      ContainerDto::Root(root) => root.id,
+      ContainerDto::Monitor(monitor) => monitor.id,
+      ContainerDto::Workspace(workspace) => workspace.id,
+      ContainerDto::Split(split) => split.id,
+      ContainerDto::Window(window) => window.id,
+    }
+  }
+
+  /// ID of the container's parent. `None` for the root container and
+  /// for detached containers.
+  pub fn parent_id(&self) -> Option<Uuid> {
+    match self {
+      ContainerDto::Root(root) => root.parent_id,
+      ContainerDto::Monitor(monitor) => monitor.parent_id,
+      ContainerDto::Workspace(workspace) => workspace.parent_id,
+      ContainerDto::Split(split) => split.parent_id,
+      ContainerDto::Window(window) => window.parent_id,
+    }
+  }
+
+  /// Child containers of the container. Empty for windows.
+  pub fn children(&self) -> &[ContainerDto] {
+    match self {
+      ContainerDto::Root(root) => &root.children,
+      ContainerDto::Monitor(monitor) => &monitor.children,
+      ContainerDto::Workspace(workspace) => &workspace.children,
+      ContainerDto::Split(split) => &split.children,
+      ContainerDto::Window(_) => &[],
+    }
+  }
+
+  /// The container itself if it's a window, otherwise all descendant
+  /// windows.
+  pub fn self_and_descendant_windows(&self) -> Vec<&WindowDto> {
+    match self {
+      ContainerDto::Window(window) => vec![window],
+      _ => self
+        .children()
+        .iter()
+        .flat_map(|child| child.self_and_descendant_windows())
+        .collect(),
+    }
+  }
+
+  /// Mutable references to the child containers and their focus order.
+  /// Returns `None` for windows, since they can't have children.
+  fn children_mut(
+    &mut self,
+  ) -> Option<(&mut Vec<ContainerDto>, &mut Vec<Uuid>)> {
+    match self {
+      ContainerDto::Root(root) => {
+        Some((&mut root.children, &mut root.child_focus_order))
+      }
+      ContainerDto::Monitor(monitor) => {
+        Some((&mut monitor.children, &mut monitor.child_focus_order))
+      }
+      ContainerDto::Workspace(workspace) => {
+        Some((&mut workspace.children, &mut workspace.child_focus_order))
+      }
+      ContainerDto::Split(split) => {
+        Some((&mut split.children, &mut split.child_focus_order))
+      }
+      ContainerDto::Window(_) => None,
+    }
+  }
+
+  /// Removes descendants that are nested deeper than the given depth.
+  ///
+  /// A depth of 0 removes all children, a depth of 1 keeps only direct
+  /// children, and so on. The focus order only keeps the IDs of the
+  /// remaining children.
+  pub fn truncate_depth(&mut self, depth: usize) {
+    if let Some((children, child_focus_order)) = self.children_mut() {
+      match depth {
+        0 => children.clear(),
+        _ => {
+          for child in children.iter_mut() {
+            child.truncate_depth(depth - 1);
+          }
+        }
+      }
+
+      child_focus_order
+        .retain(|id| children.iter().any(|child| child.id() == *id));
+    }
+  }
+}
@@ -0,0 +1,13 @@
+mod container_dto;
+mod monitor_dto;
+mod root_container_dto;
+mod split_container_dto;
+mod window_dto;
+mod workspace_dto;
+
+pub use container_dto::*;
+pub use monitor_dto::*;
+pub use root_container_dto::*;
+pub use split_container_dto::*;
+pub use window_dto::*;
+pub use workspace_dto::*;
@@ -1,13 +1,13 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::{ActiveDrag, WindowState};
-use crate::common::{DisplayState, Rect, RectDelta};
+use crate::{ActiveDrag, DisplayState, Rect, RectDelta, WindowState};
 
 /// User-friendly representation of a tiling or non-tiling window.
 ///
 /// Used for IPC and debug logging.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowDto {
   pub id: Uuid,
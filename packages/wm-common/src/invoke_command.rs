@@ -0,0 +1,601 @@
+use std::{borrow::Cow, fmt, iter, str::FromStr};
+
+use clap::{
+  error::KindFormatter, Args, CommandFactory, Parser, ValueEnum,
+};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+  offending_arg, parse_command_args, parse_command_chain,
+  quote_command_arg, ChainOperator, CommandArg, CommandSyntaxError,
+  Direction, LengthValue, TilingDirection,
+};
+
+#[derive(Clone, Debug, Parser, PartialEq, Serialize)]
+pub enum InvokeCommand {
+  AdjustBorders(InvokeAdjustBordersCommand),
+  Close,
+  Focus(InvokeFocusCommand),
+  Ignore,
+  Move(InvokeMoveCommand),
+  MoveWorkspace {
+    #[clap(long)]
+    direction: Direction,
+  },
+  Resize(InvokeResizeCommand),
+  SetFloating {
+    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
+    shown_on_top: Option<bool>,
+
+    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
+    centered: Option<bool>,
+  },
+  SetFullscreen {
+    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
+    shown_on_top: Option<bool>,
+
+    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
+    maximized: Option<bool>,
+  },
+  SetMinimized,
+  SetTiling,
+  SetTitleBarVisibility {
+    #[clap(required = true, value_enum)]
+    visibility: TitleBarVisibility,
+  },
+  ShellExec {
+    #[clap(long, action)]
+    hide_window: bool,
+
+    #[clap(required = true, trailing_var_arg = true)]
+    command: Vec<String>,
+  },
+  // Reuse `InvokeResizeCommand` struct.
+  Size(InvokeResizeCommand),
+  ToggleFloating {
+    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
+    shown_on_top: Option<bool>,
+
+    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
+    centered: Option<bool>,
+  },
+  ToggleFullscreen {
+    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
+    shown_on_top: Option<bool>,
+
+    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
+    maximized: Option<bool>,
+  },
+  ToggleMinimized,
+  ToggleTiling,
+  ToggleTilingDirection,
+  SetTilingDirection {
+    #[clap(required = true)]
+    tiling_direction: TilingDirection,
+  },
+  WmCycleFocus {
+    #[clap(long, default_value_t = false)]
+    omit_fullscreen: bool,
+
+    #[clap(long, default_value_t = true)]
+    omit_minimized: bool,
+  },
+  WmDisableBindingMode {
+    #[clap(long)]
+    name: String,
+  },
+  WmEnableBindingMode {
+    #[clap(long)]
+    name: String,
+  },
+  WmExit,
+  WmRedraw,
+  WmReloadConfig,
+}
+
+impl InvokeCommand {
+  /// Parses a command from arguments that were split from the given
+  /// command string. Parse errors point to the offending argument.
+  pub fn from_args(
+    unparsed: &str,
+    args: &[CommandArg],
+  ) -> anyhow::Result<Self> {
+    // Clap expects an array of string slices where the first argument is
+    // the binary name/path. We therefore have to prepend an additional
+    // empty argument.
+    let args_iter =
+      iter::once("").chain(args.iter().map(|arg| arg.value.as_str()));
+
+    InvokeCommand::try_parse_from(args_iter).map_err(|err| {
+      let offending_arg = offending_arg(&err, args);
+
+      // Format the error message and remove the "error: " prefix.
+      let err_msg = err.apply::<KindFormatter>().to_string();
+      let err_msg = err_msg.trim_start_matches("error: ").trim_end();
+
+      match offending_arg {
+        Some(arg) => {
+          CommandSyntaxError::at_arg(err_msg, unparsed, arg).into()
+        }
+        None => anyhow::Error::msg(err_msg.to_string()),
+      }
+    })
+  }
+}
+
+impl FromStr for InvokeCommand {
+  type Err = anyhow::Error;
+
+  fn from_str(unparsed: &str) -> Result<Self, Self::Err> {
+    let args = parse_command_args(unparsed)?;
+    Self::from_args(unparsed, &args)
+  }
+}
+
+impl<'de> Deserialize<'de> for InvokeCommand {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    let unparsed = String::deserialize(deserializer)?;
+
+    unparsed
+      .parse::<InvokeCommand>()
+      .map_err(|err| serde::de::Error::custom(err.to_string()))
+  }
+}
+
+/// Command along with the operator that chains it to the previous
+/// command (e.g. the `&&` in `focus --workspace 1 && close`).
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainedCommand {
+  pub command: InvokeCommand,
+
+  /// Operator that joins the command to the previous one. `None` for
+  /// the first command.
+  pub operator: Option<ChainOperator>,
+}
+
+impl ChainedCommand {
+  /// Whether the command should run, given whether the previous command
+  /// succeeded. Only commands chained with `;` run after a failure.
+  #[must_use]
+  pub fn should_run(&self, is_prev_success: bool) -> bool {
+    is_prev_success || self.operator == Some(ChainOperator::Sequence)
+  }
+}
+
+impl From<InvokeCommand> for ChainedCommand {
+  fn from(command: InvokeCommand) -> Self {
+    Self {
+      command,
+      operator: None,
+    }
+  }
+}
+
+/// Deserializes a list of command strings, where each string can hold
+/// multiple commands chained with `;` or `&&`.
+///
+/// The chained commands are flattened into the list, keeping their
+/// operators. Separate strings in the list are chained with `&&`, such
+/// that commands run in order until one fails.
+pub(crate) fn deserialize_command_list<'de, D>(
+  deserializer: D,
+) -> Result<Vec<ChainedCommand>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let mut commands = Vec::new();
+
+  for unparsed in Vec::<String>::deserialize(deserializer)? {
+    let segments = parse_command_chain(&unparsed)
+      .map_err(|err| serde::de::Error::custom(err.to_string()))?;
+
+    for segment in segments {
+      let command = InvokeCommand::from_args(&unparsed, &segment.args)
+        .map_err(|err| serde::de::Error::custom(err.to_string()))?;
+
+      let operator = match commands.is_empty() {
+        true => None,
+        false => segment.operator.or(Some(ChainOperator::And)),
+      };
+
+      commands.push(ChainedCommand { command, operator });
+    }
+  }
+
+  Ok(commands)
+}
+
+/// Commands are written as strings in the config (e.g. `focus --workspace
+/// 1`), but are serialized as structs in IPC messages.
+impl JsonSchema for InvokeCommand {
+  fn schema_name() -> Cow<'static, str> {
+    "InvokeCommand".into()
+  }
+
+  fn json_schema(_: &mut SchemaGenerator) -> Schema {
+    let command_names = InvokeCommand::command()
+      .get_subcommands()
+      .map(|subcommand| subcommand.get_name().to_string())
+      .collect::<Vec<_>>();
+
+    json_schema!({
+      "anyOf": [
+        {
+          "type": "string",
+          "pattern": format!("^({})(\\s.*)?$", command_names.join("|")),
+          "description": "WM command (e.g. `focus --workspace 1`)."
+        },
+        { "type": "object" }
+      ]
+    })
+  }
+}
+
+impl fmt::Display for InvokeCommand {
+  /// Formats the command in the same syntax that it's parsed from (e.g.
+  /// `focus --workspace 1`), so that it can be sent over IPC.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let args = match self {
+      InvokeCommand::AdjustBorders(args) => vec![
+        Some("adjust-borders".to_string()),
+        value_flag("top", &args.top),
+        value_flag("right", &args.right),
+        value_flag("bottom", &args.bottom),
+        value_flag("left", &args.left),
+      ],
+      InvokeCommand::Close => vec![Some("close".to_string())],
+      InvokeCommand::Focus(args) => vec![
+        Some("focus".to_string()),
+        value_flag("direction", &args.direction),
+        value_flag("workspace", &args.workspace),
+        value_flag("monitor", &args.monitor),
+        bool_flag("next-active-workspace", args.next_active_workspace),
+        bool_flag("prev-active-workspace", args.prev_active_workspace),
+        bool_flag("next-workspace", args.next_workspace),
+        bool_flag("prev-workspace", args.prev_workspace),
+        bool_flag("recent-workspace", args.recent_workspace),
+      ],
+      InvokeCommand::Ignore => vec![Some("ignore".to_string())],
+      InvokeCommand::Move(args) => vec![
+        Some("move".to_string()),
+        value_flag("direction", &args.direction),
+        value_flag("workspace", &args.workspace),
+        bool_flag("next-active-workspace", args.next_active_workspace),
+        bool_flag("prev-active-workspace", args.prev_active_workspace),
+        bool_flag("next-workspace", args.next_workspace),
+        bool_flag("prev-workspace", args.prev_workspace),
+        bool_flag("recent-workspace", args.recent_workspace),
+      ],
+      InvokeCommand::MoveWorkspace { direction } => vec![
+        Some("move-workspace".to_string()),
+        value_flag("direction", &Some(direction)),
+      ],
+      InvokeCommand::Resize(args) => vec![
+        Some("resize".to_string()),
+        value_flag("width", &args.width),
+        value_flag("height", &args.height),
+      ],
+      InvokeCommand::SetFloating {
+        shown_on_top,
+        centered,
+      } => vec![
+        Some("set-floating".to_string()),
+        value_flag("shown-on-top", shown_on_top),
+        value_flag("centered", centered),
+      ],
+      InvokeCommand::SetFullscreen {
+        shown_on_top,
+        maximized,
+      } => vec![
+        Some("set-fullscreen".to_string()),
+        value_flag("shown-on-top", shown_on_top),
+        value_flag("maximized", maximized),
+      ],
+      InvokeCommand::SetMinimized => {
+        vec![Some("set-minimized".to_string())]
+      }
+      InvokeCommand::SetTiling => vec![Some("set-tiling".to_string())],
+      InvokeCommand::SetTitleBarVisibility { visibility } => vec![
+        Some("set-title-bar-visibility".to_string()),
+        visibility
+          .to_possible_value()
+          .map(|value| value.get_name().to_string()),
+      ],
+      InvokeCommand::ShellExec {
+        hide_window,
+        command,
+      } => iter::once(Some("shell-exec".to_string()))
+        .chain(iter::once(bool_flag("hide-window", *hide_window)))
+        .chain(
+          command
+            .iter()
+            .map(|arg| Some(quote_command_arg(arg).into())),
+        )
+        .collect(),
+      InvokeCommand::Size(args) => vec![
+        Some("size".to_string()),
+        value_flag("width", &args.width),
+        value_flag("height", &args.height),
+      ],
+      InvokeCommand::ToggleFloating {
+        shown_on_top,
+        centered,
+      } => vec![
+        Some("toggle-floating".to_string()),
+        value_flag("shown-on-top", shown_on_top),
+        value_flag("centered", centered),
+      ],
+      InvokeCommand::ToggleFullscreen {
+        shown_on_top,
+        maximized,
+      } => vec![
+        Some("toggle-fullscreen".to_string()),
+        value_flag("shown-on-top", shown_on_top),
+        value_flag("maximized", maximized),
+      ],
+      InvokeCommand::ToggleMinimized => {
+        vec![Some("toggle-minimized".to_string())]
+      }
+      InvokeCommand::ToggleTiling => {
+        vec![Some("toggle-tiling".to_string())]
+      }
+      InvokeCommand::ToggleTilingDirection => {
+        vec![Some("toggle-tiling-direction".to_string())]
+      }
+      InvokeCommand::SetTilingDirection { tiling_direction } => vec![
+        Some("set-tiling-direction".to_string()),
+        Some(tiling_direction.to_string()),
+      ],
+      InvokeCommand::WmCycleFocus {
+        omit_fullscreen,
+        omit_minimized,
+      } => vec![
+        Some("wm-cycle-focus".to_string()),
+        bool_flag("omit-fullscreen", *omit_fullscreen),
+        bool_flag("omit-minimized", *omit_minimized),
+      ],
+      InvokeCommand::WmDisableBindingMode { name } => vec![
+        Some("wm-disable-binding-mode".to_string()),
+        value_flag("name", &Some(name)),
+      ],
+      InvokeCommand::WmEnableBindingMode { name } => vec![
+        Some("wm-enable-binding-mode".to_string()),
+        value_flag("name", &Some(name)),
+      ],
+      InvokeCommand::WmExit => vec![Some("wm-exit".to_string())],
+      InvokeCommand::WmRedraw => vec![Some("wm-redraw".to_string())],
+      InvokeCommand::WmReloadConfig => {
+        vec![Some("wm-reload-config".to_string())]
+      }
+    };
+
+    let args = args.into_iter().flatten().collect::<Vec<_>>();
+    write!(f, "{}", args.join(" "))
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, ValueEnum)]
+#[clap(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TitleBarVisibility {
+  Shown,
+  Hidden,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Serialize)]
+#[group(required = true, multiple = true)]
+pub struct InvokeAdjustBordersCommand {
+  #[clap(long, allow_hyphen_values = true)]
+  pub top: Option<LengthValue>,
+
+  #[clap(long, allow_hyphen_values = true)]
+  pub right: Option<LengthValue>,
+
+  #[clap(long, allow_hyphen_values = true)]
+  pub bottom: Option<LengthValue>,
+
+  #[clap(long, allow_hyphen_values = true)]
+  pub left: Option<LengthValue>,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Serialize)]
+#[group(required = true, multiple = false)]
+pub struct InvokeFocusCommand {
+  #[clap(long)]
+  pub direction: Option<Direction>,
+
+  #[clap(long)]
+  pub workspace: Option<String>,
+
+  #[clap(long)]
+  pub monitor: Option<usize>,
+
+  #[clap(long)]
+  pub next_active_workspace: bool,
+
+  #[clap(long)]
+  pub prev_active_workspace: bool,
+
+  #[clap(long)]
+  pub next_workspace: bool,
+
+  #[clap(long)]
+  pub prev_workspace: bool,
+
+  #[clap(long)]
+  pub recent_workspace: bool,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Serialize)]
+#[group(required = true, multiple = false)]
+pub struct InvokeMoveCommand {
+  /// Direction to move the window.
+  #[clap(long)]
+  pub direction: Option<Direction>,
+
+  /// Name of workspace to move the window.
+  #[clap(long)]
+  pub workspace: Option<String>,
+
+  #[clap(long)]
+  pub next_active_workspace: bool,
+
+  #[clap(long)]
+  pub prev_active_workspace: bool,
+
+  #[clap(long)]
+  pub next_workspace: bool,
+
+  #[clap(long)]
+  pub prev_workspace: bool,
+
+  #[clap(long)]
+  pub recent_workspace: bool,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Serialize)]
+#[group(required = true, multiple = true)]
+pub struct InvokeResizeCommand {
+  #[clap(long, allow_hyphen_values = true)]
+  pub width: Option<LengthValue>,
+
+  #[clap(long, allow_hyphen_values = true)]
+  pub height: Option<LengthValue>,
+}
+
+/// Formats a flag with a value (e.g. `--workspace=1`). The value is
+/// quoted if needed. Returns `None` if the value isn't set.
+fn value_flag<T: fmt::Display>(
+  name: &str,
+  value: &Option<T>,
+) -> Option<String> {
+  value.as_ref().map(|value| {
+    format!("--{}={}", name, quote_command_arg(&value.to_string()))
+  })
+}
+
+/// Formats a boolean flag (e.g. `--hide-window`). Returns `None` if the
+/// flag isn't set.
+fn bool_flag(name: &str, value: bool) -> Option<String> {
+  value.then(|| format!("--{}", name))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Command strings that cover every variant of `InvokeCommand`.
+  const COMMANDS: &[&str] = &[
+    "adjust-borders --top=10px --right=-5% --bottom 0px --left 2px",
+    "close",
+    "focus --direction left",
+    "focus --workspace 'my workspace'",
+    "focus --monitor 1",
+    "focus --next-active-workspace",
+    "focus --prev-active-workspace",
+    "focus --next-workspace",
+    "focus --prev-workspace",
+    "focus --recent-workspace",
+    "ignore",
+    "move --direction right",
+    "move --workspace \"it's\"",
+    "move --next-active-workspace",
+    "move --prev-active-workspace",
+    "move --next-workspace",
+    "move --prev-workspace",
+    "move --recent-workspace",
+    "move-workspace --direction up",
+    "resize --width +10% --height -20px",
+    "set-floating",
+    "set-floating --shown-on-top --centered=false",
+    "set-fullscreen --maximized=false",
+    "set-minimized",
+    "set-tiling",
+    "set-title-bar-visibility hidden",
+    "shell-exec --hide-window cmd /c 'echo hi; exit' C:\\Windows",
+    "shell-exec code ''",
+    "shell-exec explorer 'C:\\Users\\' '&' a\\&b",
+    "size --width 50%",
+    "toggle-floating --centered",
+    "toggle-fullscreen --shown-on-top=false --maximized",
+    "toggle-minimized",
+    "toggle-tiling",
+    "toggle-tiling-direction",
+    "set-tiling-direction horizontal",
+    "wm-cycle-focus --omit-fullscreen",
+    "wm-disable-binding-mode --name resize",
+    "wm-enable-binding-mode --name 'a && b'",
+    "wm-exit",
+    "wm-redraw",
+    "wm-reload-config",
+  ];
+
+  #[test]
+  fn display_round_trips() {
+    for unparsed in COMMANDS {
+      let command = unparsed.parse::<InvokeCommand>().unwrap();
+      let formatted = command.to_string();
+
+      assert_eq!(
+        formatted.parse::<InvokeCommand>().unwrap(),
+        command,
+        "'{}' was formatted as '{}'.",
+        unparsed,
+        formatted
+      );
+    }
+  }
+
+  #[test]
+  fn command_list_keeps_operators() {
+    #[derive(Deserialize)]
+    struct Config {
+      #[serde(deserialize_with = "deserialize_command_list")]
+      commands: Vec<ChainedCommand>,
+    }
+
+    let config: Config = serde_json::from_str(
+      r#"{ "commands": ["close; toggle-floating && focus --workspace 1", "wm-redraw"] }"#,
+    )
+    .unwrap();
+
+    let operators = config
+      .commands
+      .iter()
+      .map(|command| command.operator)
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      operators,
+      [
+        None,
+        Some(ChainOperator::Sequence),
+        Some(ChainOperator::And),
+        Some(ChainOperator::And),
+      ]
+    );
+
+    assert!(config.commands[1].should_run(false));
+    assert!(!config.commands[2].should_run(false));
+  }
+
+  #[test]
+  fn covers_every_command() {
+    for subcommand in InvokeCommand::command().get_subcommands() {
+      let name = subcommand.get_name();
+
+      assert!(
+        COMMANDS
+          .iter()
+          .any(|unparsed| unparsed.split(' ').next() == Some(name)),
+        "No round-trip test for '{}'.",
+        name
+      );
+    }
+  }
+}
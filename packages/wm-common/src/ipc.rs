@@ -0,0 +1,362 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{BindingModeConfig, ContainerDto, TilingDirection, WmEvent};
+
+/// Version of the IPC protocol.
+///
+/// Bumped on breaking changes to the message format. Additive changes
+/// are instead advertised via `IpcFeature`s.
+pub const IPC_PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(tag = "messageType", rename_all = "snake_case")]
+pub enum ServerMessage {
+  ClientResponse(ClientResponseMessage),
+  EventSubscription(EventSubscriptionMessage),
+}
+
+/// Structured message sent by IPC clients.
+///
+/// Plain CLI strings (e.g. `query windows`) are also accepted for
+/// compatibility with older clients, in which case the ID is `None`.
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientMessage {
+  /// Client-supplied ID that is echoed back in the response.
+  pub id: Option<MessageId>,
+
+  /// Command string to run (e.g. `query windows`). Ignored if `batch`
+  /// is set.
+  #[serde(default)]
+  pub command: String,
+
+  /// Batch of WM commands to run with a single redraw at the end. See
+  /// `CommandBatch`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub batch: Option<CommandBatch>,
+
+  /// IPC auth token. Only needed when the server requires auth and the
+  /// token wasn't already sent in the websocket handshake.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub token: Option<String>,
+}
+
+impl ClientMessage {
+  /// Parses a raw websocket message into a `ClientMessage`.
+  ///
+  /// Messages that don't start with `{` are treated as a plain command
+  /// string. Errors if the message looks like a JSON envelope but isn't
+  /// a valid one.
+  pub fn parse(message: &str) -> Result<Self, serde_json::Error> {
+    if message.trim_start().starts_with('{') {
+      return serde_json::from_str::<Self>(message);
+    }
+
+    Ok(Self::from_command(message))
+  }
+
+  /// Creates a message for a plain command string without an ID.
+  pub fn from_command(command: &str) -> Self {
+    Self {
+      id: None,
+      command: command.to_string(),
+      batch: None,
+      token: None,
+    }
+  }
+}
+
+/// ID of a `ClientMessage`. Either a string or a number, same as
+/// JSON-RPC request IDs.
+#[derive(
+  Clone, Debug, Deserialize, Eq, Hash, JsonSchema, PartialEq, Serialize,
+)]
+#[serde(untagged)]
+pub enum MessageId {
+  String(String),
+  Number(serde_json::Number),
+}
+
+impl From<String> for MessageId {
+  fn from(id: String) -> Self {
+    Self::String(id)
+  }
+}
+
+/// Batch of WM commands sent within a `ClientMessage`.
+///
+/// Commands run in order and the batch stops at the first command that
+/// fails. The batch isn't atomic: changes made by earlier commands are
+/// kept, and the remaining commands are reported as skipped.
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandBatch {
+  /// Command strings to run in order (e.g. `focus --workspace 1`).
+  pub commands: Vec<String>,
+
+  /// Container to run the first command with. Defaults to the focused
+  /// container.
+  pub subject_container_id: Option<Uuid>,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientResponseMessage {
+  pub client_message: String,
+  pub client_message_id: Option<MessageId>,
+  pub data: Option<ClientResponseData>,
+  pub error: Option<String>,
+  pub success: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(untagged)]
+pub enum ClientResponseData {
+  // Needs to come before `AppMetadata`, since untagged variants are
+  // tried in order and `AppMetadataData` is a subset of
+  // `CapabilitiesData`.
+  Capabilities(CapabilitiesData),
+  AppMetadata(AppMetadataData),
+  BindingModes(BindingModesData),
+  Container(ContainerData),
+  // Needs to come before `Command`, since untagged variants are tried in
+  // order and `CommandData` is a subset of `CommandBatchData`.
+  CommandBatch(CommandBatchData),
+  Command(CommandData),
+  EventSubscribe(EventSubscribeData),
+  EventUnsubscribe,
+  Focused(FocusedData),
+  Monitors(MonitorsData),
+  TilingDirection(TilingDirectionData),
+  Tree(TreeData),
+  Windows(WindowsData),
+  Workspaces(WorkspacesData),
+  /// Response data with a subset of fields (e.g. via `--fields`). Needs
+  /// to be last, since it matches any JSON value.
+  Projected(Value),
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppMetadataData {
+  pub version: String,
+
+  /// Not set by servers that predate protocol versioning.
+  #[serde(default)]
+  pub protocol_version: Option<u32>,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapabilitiesData {
+  /// Application version of the server (e.g. `3.1.0`).
+  pub version: String,
+
+  /// IPC protocol version of the server. See `IPC_PROTOCOL_VERSION`.
+  pub protocol_version: u32,
+
+  /// Names of the supported query subcommands (e.g. `windows`).
+  pub queries: Vec<String>,
+
+  /// Names of the events that can be subscribed to (e.g.
+  /// `window_managed`).
+  pub events: Vec<String>,
+
+  /// Names of the supported optional features (e.g. `command_batch`).
+  /// Kept as strings, so that clients can handle features added by
+  /// newer servers.
+  pub features: Vec<String>,
+}
+
+impl CapabilitiesData {
+  /// Whether the server supports the given feature.
+  pub fn has_feature(&self, feature: IpcFeature) -> bool {
+    self.features.iter().any(|name| name == feature.as_str())
+  }
+
+  /// Whether the server supports the given query subcommand (e.g.
+  /// `windows`).
+  pub fn has_query(&self, query: &str) -> bool {
+    self.queries.iter().any(|name| name == query)
+  }
+
+  /// Whether the server supports subscribing to the given event (e.g.
+  /// `window_managed`).
+  pub fn has_event(&self, event: &str) -> bool {
+    self.events.iter().any(|name| name == event)
+  }
+}
+
+/// Optional IPC features that a server can advertise via
+/// `query capabilities`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IpcFeature {
+  /// Multiple commands in a single message via `ClientMessage::batch`.
+  CommandBatch,
+  /// Quoted arguments and commands chained with `;` or `&&` (e.g.
+  /// `command focus --workspace 1 && move --workspace 2`).
+  CommandChains,
+  /// `user_config_reload_failed` event when the config fails to reload
+  /// after a file change.
+  ConfigReloadFailedEvent,
+  /// Event subscriptions filtered by container, workspace, monitor or
+  /// window (e.g. `sub -e focus_changed --workspace 1`).
+  EventFilters,
+  /// Sequence numbers and lag reporting on event messages.
+  EventSequence,
+  /// Close frames on shutdown and pings to keep connections alive.
+  GracefulShutdown,
+  /// Queries, commands and events over plain HTTP and server-sent
+  /// events (e.g. `GET /query/windows`), if enabled in the config.
+  HttpGateway,
+  /// JSON message envelope with a client-supplied ID.
+  MessageIds,
+  /// Container queries filtered by window properties, with optional
+  /// field projection (e.g. `--fields id,title`).
+  QueryFilters,
+  /// Auth token in the handshake or in `ClientMessage::token`, if
+  /// required by the config.
+  TokenAuth,
+  /// `query tree` and `query container --id` with an optional depth.
+  TreeQueries,
+  /// Event filters as used by `wait-for`, where window filters also
+  /// match removed containers (e.g. on `window_unmanaged`) and events
+  /// without a container are excluded.
+  WaitForEvents,
+}
+
+impl IpcFeature {
+  /// All features supported by this version of the protocol.
+  pub const ALL: [IpcFeature; 12] = [
+    IpcFeature::CommandBatch,
+    IpcFeature::CommandChains,
+    IpcFeature::ConfigReloadFailedEvent,
+    IpcFeature::EventFilters,
+    IpcFeature::EventSequence,
+    IpcFeature::GracefulShutdown,
+    IpcFeature::HttpGateway,
+    IpcFeature::MessageIds,
+    IpcFeature::QueryFilters,
+    IpcFeature::TokenAuth,
+    IpcFeature::TreeQueries,
+    IpcFeature::WaitForEvents,
+  ];
+
+  /// Name of the feature as advertised in `CapabilitiesData`.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      IpcFeature::CommandBatch => "command_batch",
+      IpcFeature::CommandChains => "command_chains",
+      IpcFeature::ConfigReloadFailedEvent => "config_reload_failed_event",
+      IpcFeature::EventFilters => "event_filters",
+      IpcFeature::EventSequence => "event_sequence",
+      IpcFeature::GracefulShutdown => "graceful_shutdown",
+      IpcFeature::HttpGateway => "http_gateway",
+      IpcFeature::MessageIds => "message_ids",
+      IpcFeature::QueryFilters => "query_filters",
+      IpcFeature::TokenAuth => "token_auth",
+      IpcFeature::TreeQueries => "tree_queries",
+      IpcFeature::WaitForEvents => "wait_for_events",
+    }
+  }
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BindingModesData {
+  pub binding_modes: Vec<BindingModeConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerData {
+  pub container: ContainerDto,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandData {
+  pub subject_container_id: Uuid,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandBatchData {
+  /// Result of each command in the batch, in the order they were sent.
+  /// Commands after the first failing command are marked as skipped.
+  pub results: Vec<CommandResult>,
+  pub subject_container_id: Uuid,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandResult {
+  pub command: String,
+  pub error: Option<String>,
+  pub success: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSubscribeData {
+  pub subscription_id: Uuid,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusedData {
+  pub focused: ContainerDto,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorsData {
+  pub monitors: Vec<ContainerDto>,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TilingDirectionData {
+  pub tiling_direction: TilingDirection,
+  pub direction_container: ContainerDto,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeData {
+  pub root: ContainerDto,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowsData {
+  pub windows: Vec<ContainerDto>,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspacesData {
+  pub workspaces: Vec<ContainerDto>,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSubscriptionMessage {
+  pub data: Option<WmEvent>,
+  pub error: Option<String>,
+
+  /// Number of events that were dropped because the subscriber fell
+  /// behind. Clients should re-sync by querying the WM state when this
+  /// is set.
+  pub lagged_count: Option<u64>,
+
+  /// Monotonically increasing sequence number of the event. Not set for
+  /// lag messages.
+  pub sequence: Option<u64>,
+
+  pub subscription_id: Uuid,
+  pub success: bool,
+}
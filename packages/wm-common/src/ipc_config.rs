@@ -0,0 +1,269 @@
+use std::{env, fs, path::PathBuf};
+
+use anyhow::Context;
+use clap::{Args, ValueEnum};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::parsed_config::default_bool;
+
+pub const DEFAULT_IPC_ADDRESS: &str = "127.0.0.1";
+
+pub const DEFAULT_IPC_PORT: u16 = 6123;
+
+/// Environment variable for overriding the IPC server address.
+pub const IPC_ADDRESS_ENV: &str = "GLAZEWM_IPC_ADDRESS";
+
+/// Environment variable for overriding the IPC server port.
+pub const IPC_PORT_ENV: &str = "GLAZEWM_IPC_PORT";
+
+/// Environment variable for overriding the IPC server transport.
+pub const IPC_TRANSPORT_ENV: &str = "GLAZEWM_IPC_TRANSPORT";
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct IpcConfig {
+  /// Address that the IPC server listens on.
+  #[serde(default = "default_ipc_address")]
+  pub address: String,
+
+  /// Port that the IPC server listens on.
+  #[serde(default = "default_ipc_port")]
+  pub port: u16,
+
+  /// Transport that the IPC server is served over.
+  #[serde(default)]
+  pub transport: IpcTransport,
+
+  /// Whether clients must present the auth token that the WM writes to
+  /// `~/.glzr/glazewm/` on startup.
+  #[serde(default = "default_bool::<false>")]
+  pub require_auth: bool,
+
+  /// Number of WM events to buffer per subscriber. Subscribers that fall
+  /// further behind receive a lag message with the number of dropped
+  /// events.
+  #[serde(default = "default_event_buffer_size")]
+  pub event_buffer_size: usize,
+
+  /// Interval in seconds at which clients are pinged to keep their
+  /// connection alive.
+  #[serde(default = "default_ping_interval")]
+  pub ping_interval: u64,
+
+  /// Time in seconds after which connections that haven't sent any
+  /// messages (including pongs) are closed. Set to 0 to disable.
+  #[serde(default = "default_idle_timeout")]
+  pub idle_timeout: u64,
+
+  /// Config for answering plain HTTP requests alongside the websocket
+  /// server.
+  #[serde(default)]
+  pub http: IpcHttpConfig,
+}
+
+#[derive(
+  Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize,
+)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct IpcHttpConfig {
+  /// Whether to serve queries, commands and events over HTTP (e.g.
+  /// `GET /query/windows`). Requests from browsers (i.e. with an
+  /// `Origin` header) are rejected.
+  #[serde(default = "default_bool::<false>")]
+  pub enabled: bool,
+
+  /// TCP port to serve HTTP on. HTTP is served on the same port (or
+  /// local socket) as the websocket server if not set.
+  #[serde(default)]
+  pub port: Option<u16>,
+}
+
+impl IpcConfig {
+  /// Applies overrides from the `GLAZEWM_IPC_ADDRESS`,
+  /// `GLAZEWM_IPC_PORT` and `GLAZEWM_IPC_TRANSPORT` environment
+  /// variables, followed by the given CLI flags.
+  pub fn with_overrides(mut self, args: &IpcArgs) -> anyhow::Result<Self> {
+    if let Ok(address) = env::var(IPC_ADDRESS_ENV) {
+      self.address = address;
+    }
+
+    if let Ok(port) = env::var(IPC_PORT_ENV) {
+      self.port = port.parse().with_context(|| {
+        format!("Invalid port '{}' in {}.", port, IPC_PORT_ENV)
+      })?;
+    }
+
+    if let Ok(transport) = env::var(IPC_TRANSPORT_ENV) {
+      self.transport = IpcTransport::from_str(&transport, true)
+        .map_err(anyhow::Error::msg)
+        .with_context(|| {
+          format!(
+            "Invalid transport '{}' in {}.",
+            transport, IPC_TRANSPORT_ENV
+          )
+        })?;
+    }
+
+    if let Some(port) = args.port {
+      self.port = port;
+    }
+
+    if let Some(transport) = &args.transport {
+      self.transport = transport.clone();
+    }
+
+    Ok(self)
+  }
+
+  /// Socket address of the IPC server (e.g. `127.0.0.1:6123`).
+  pub fn socket_addr(&self) -> String {
+    format!("{}:{}", self.address, self.port)
+  }
+
+  /// Path of the local socket for this port.
+  ///
+  /// This is a named pipe on Windows (e.g. `\\.\pipe\glazewm-ipc-6123`)
+  /// and a Unix domain socket elsewhere (e.g.
+  /// `~/.glzr/glazewm/ipc/6123.sock`). The socket gets a dedicated
+  /// directory, since access to it is restricted via the directory's
+  /// permissions.
+  pub fn local_socket_path(&self) -> anyhow::Result<PathBuf> {
+    if cfg!(windows) {
+      return Ok(PathBuf::from(format!(
+        r"\\.\pipe\glazewm-ipc-{}",
+        self.port
+      )));
+    }
+
+    let socket_path = home::home_dir()
+      .context("Unable to get home directory.")?
+      .join(format!(".glzr/glazewm/ipc/{}.sock", self.port));
+
+    Ok(socket_path)
+  }
+
+  /// Path to the IPC auth token file for this port (e.g.
+  /// `~/.glzr/glazewm/ipc-6123.token`).
+  pub fn auth_token_path(&self) -> anyhow::Result<PathBuf> {
+    let token_path = home::home_dir()
+      .context("Unable to get home directory.")?
+      .join(format!(".glzr/glazewm/ipc-{}.token", self.port));
+
+    Ok(token_path)
+  }
+
+  /// Reads the IPC auth token if the WM has written one.
+  pub fn read_auth_token(&self) -> Option<String> {
+    self
+      .auth_token_path()
+      .ok()
+      .and_then(|token_path| fs::read_to_string(token_path).ok())
+      .map(|token| token.trim().to_string())
+  }
+}
+
+/// Checks an auth token given by a client against the expected token.
+///
+/// Compares in constant time, so that the token can't be guessed from
+/// how long the comparison takes.
+pub fn is_auth_token_match(expected: &str, given: Option<&str>) -> bool {
+  let Some(given) = given else {
+    return false;
+  };
+
+  // The length of the token isn't secret, so it's fine to return early.
+  if expected.len() != given.len() {
+    return false;
+  }
+
+  expected
+    .bytes()
+    .zip(given.bytes())
+    .fold(0, |diff, (expected, given)| diff | (expected ^ given))
+    == 0
+}
+
+impl Default for IpcConfig {
+  fn default() -> Self {
+    IpcConfig {
+      address: default_ipc_address(),
+      port: default_ipc_port(),
+      transport: IpcTransport::default(),
+      require_auth: false,
+      event_buffer_size: default_event_buffer_size(),
+      ping_interval: default_ping_interval(),
+      idle_timeout: default_idle_timeout(),
+      http: IpcHttpConfig::default(),
+    }
+  }
+}
+
+#[derive(
+  Clone,
+  Debug,
+  Default,
+  Deserialize,
+  JsonSchema,
+  PartialEq,
+  Serialize,
+  ValueEnum,
+)]
+#[clap(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum IpcTransport {
+  /// Websocket server on a TCP port.
+  #[default]
+  Tcp,
+  /// Websocket server on a named pipe (Windows) or a Unix domain socket.
+  /// Access is restricted by filesystem permissions and no TCP port is
+  /// opened.
+  LocalSocket,
+}
+
+/// IPC connection flags to be used with `#[command(flatten)]`.
+#[derive(Args, Clone, Debug, Default)]
+#[clap(about = None, long_about = None)]
+pub struct IpcArgs {
+  /// Port of the IPC server.
+  ///
+  /// Overrides the `GLAZEWM_IPC_PORT` environment variable and the
+  /// `general.ipc.port` config option.
+  #[clap(long, global = true)]
+  pub port: Option<u16>,
+
+  /// Transport of the IPC server.
+  ///
+  /// Overrides the `GLAZEWM_IPC_TRANSPORT` environment variable and the
+  /// `general.ipc.transport` config option.
+  #[clap(long, global = true, value_enum)]
+  pub transport: Option<IpcTransport>,
+}
+
+/// Helper function for setting a default value for the IPC address.
+fn default_ipc_address() -> String {
+  DEFAULT_IPC_ADDRESS.to_string()
+}
+
+/// Helper function for setting a default value for the IPC port.
+const fn default_ipc_port() -> u16 {
+  DEFAULT_IPC_PORT
+}
+
+/// Helper function for setting a default value for the IPC event buffer
+/// size.
+const fn default_event_buffer_size() -> usize {
+  16
+}
+
+/// Helper function for setting a default value for the IPC ping
+/// interval.
+const fn default_ping_interval() -> u64 {
+  30
+}
+
+/// Helper function for setting a default value for the IPC idle
+/// timeout.
+const fn default_idle_timeout() -> u64 {
+  90
+}
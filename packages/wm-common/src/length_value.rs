@@ -1,7 +1,8 @@
-use std::str::FromStr;
+use std::{borrow::Cow, fmt, str::FromStr};
 
 use anyhow::{bail, Context};
 use regex::Regex;
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
 use serde::{Deserialize, Deserializer, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -10,7 +11,7 @@ pub struct LengthValue {
   pub unit: LengthUnit,
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum LengthUnit {
   Percentage,
@@ -50,7 +51,7 @@ impl FromStr for LengthValue {
   ///
   /// Example:
   /// ```
-  /// # use wm::common::{LengthValue, LengthUnit};
+  /// # use wm_common::{LengthValue, LengthUnit};
   /// # use std::str::FromStr;
   /// let check = LengthValue {
   ///   amount: 100.0,
@@ -92,6 +93,19 @@ impl FromStr for LengthValue {
   }
 }
 
+impl fmt::Display for LengthValue {
+  /// Formats the length value in the same syntax that it's parsed from
+  /// (e.g. `10px` or `50%`).
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.unit {
+      LengthUnit::Pixel => write!(f, "{}px", self.amount.round()),
+      LengthUnit::Percentage => {
+        write!(f, "{}%", (self.amount * 100.0).round())
+      }
+    }
+  }
+}
+
 /// Deserialize a `LengthValue` from either a string or a struct.
 impl<'de> Deserialize<'de> for LengthValue {
   fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -113,3 +127,31 @@ impl<'de> Deserialize<'de> for LengthValue {
     }
   }
 }
+
+/// Schema for either a string with a unit (e.g. `10px` or `50%`) or a
+/// struct, matching the accepted formats in `Deserialize`.
+impl JsonSchema for LengthValue {
+  fn schema_name() -> Cow<'static, str> {
+    "LengthValue".into()
+  }
+
+  fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+      "anyOf": [
+        {
+          "type": "string",
+          "pattern": "^[+-]?\\d+(%|px)?$",
+          "description": "Length in pixels or percent (e.g. `10px`)."
+        },
+        {
+          "type": "object",
+          "properties": {
+            "amount": { "type": "number" },
+            "unit": generator.subschema_for::<LengthUnit>()
+          },
+          "required": ["amount", "unit"]
+        }
+      ]
+    })
+  }
+}
@@ -0,0 +1,37 @@
+mod active_drag;
+mod color;
+mod command_parser;
+mod direction;
+mod display_state;
+mod dtos;
+mod invoke_command;
+mod ipc;
+mod ipc_config;
+mod length_value;
+mod parsed_config;
+mod point;
+mod rect;
+mod rect_delta;
+mod schema;
+mod tiling_direction;
+mod window_state;
+mod wm_event;
+
+pub use active_drag::*;
+pub use color::*;
+pub use command_parser::*;
+pub use direction::*;
+pub use display_state::*;
+pub use dtos::*;
+pub use invoke_command::*;
+pub use ipc::*;
+pub use ipc_config::*;
+pub use length_value::*;
+pub use parsed_config::*;
+pub use point::*;
+pub use rect::*;
+pub use rect_delta::*;
+pub use schema::*;
+pub use tiling_direction::*;
+pub use window_state::*;
+pub use wm_event::*;
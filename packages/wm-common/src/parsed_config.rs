@@ -0,0 +1,556 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+  invoke_command::deserialize_command_list, ChainedCommand, Color,
+  InvokeCommand, IpcConfig, LengthValue, RectDelta, TilingDirection,
+};
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct ParsedConfig {
+  pub binding_modes: Vec<BindingModeConfig>,
+  pub gaps: GapsConfig,
+  pub general: GeneralConfig,
+
+  /// Paths of other config files to merge into this one (e.g.
+  /// `keybindings.yaml` or `machines/*.yaml`). Paths are relative to
+  /// the config file and can contain globs.
+  #[serde(default)]
+  pub include: Vec<String>,
+
+  pub keybindings: Vec<KeybindingConfig>,
+  pub window_behavior: WindowBehaviorConfig,
+  pub window_effects: WindowEffectsConfig,
+  pub window_rules: Vec<WindowRuleConfig>,
+
+  /// User-defined variables (e.g. `mod: alt`). Occurrences of `$name` or
+  /// `${name}` in bindings and commands are replaced with the value.
+  #[serde(default)]
+  pub variables: HashMap<String, String>,
+
+  pub workspaces: Vec<WorkspaceConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct BindingModeConfig {
+  /// Name of the binding mode.
+  pub name: String,
+
+  /// Display name of the binding mode.
+  pub display_name: Option<String>,
+
+  /// Keybindings that will be active when the binding mode is active.
+  pub keybindings: Vec<KeybindingConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct GapsConfig {
+  /// Whether to scale the gaps with the DPI of the monitor.
+  #[serde(default = "default_bool::<true>")]
+  pub scale_with_dpi: bool,
+
+  /// Gap between adjacent windows.
+  pub inner_gap: LengthValue,
+
+  /// Gap between windows and the screen edge.
+  pub outer_gap: RectDelta,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct GeneralConfig {
+  /// Config for automatically moving the cursor.
+  pub cursor_jump: CursorJumpConfig,
+
+  /// Whether to automatically focus windows underneath the cursor.
+  #[serde(default = "default_bool::<false>")]
+  pub focus_follows_cursor: bool,
+
+  /// Whether to switch back and forth between the previously focused
+  /// workspace when focusing the current workspace.
+  #[serde(default = "default_bool::<true>")]
+  pub toggle_workspace_on_refocus: bool,
+
+  /// Commands to run when the WM has started (e.g. to run a script or
+  /// launch another application).
+  #[serde(default, deserialize_with = "deserialize_command_list")]
+  #[schemars(with = "Vec<InvokeCommand>")]
+  pub startup_commands: Vec<ChainedCommand>,
+
+  /// Commands to run just before the WM is shutdown.
+  #[serde(default, deserialize_with = "deserialize_command_list")]
+  #[schemars(with = "Vec<InvokeCommand>")]
+  pub shutdown_commands: Vec<ChainedCommand>,
+
+  /// Commands to run after the WM config has reloaded.
+  #[serde(default, deserialize_with = "deserialize_command_list")]
+  #[schemars(with = "Vec<InvokeCommand>")]
+  pub config_reload_commands: Vec<ChainedCommand>,
+
+  /// Whether to reload the config automatically when the config file, or
+  /// any file that it includes, changes.
+  #[serde(default = "default_bool::<false>")]
+  pub auto_reload_config: bool,
+
+  /// Config for the IPC server.
+  #[serde(default)]
+  pub ipc: IpcConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct CursorJumpConfig {
+  /// Whether to automatically move the cursor on the specified trigger.
+  #[serde(default = "default_bool::<true>")]
+  pub enabled: bool,
+
+  /// Trigger for cursor jump.
+  #[serde(default)]
+  pub trigger: CursorJumpTrigger,
+}
+
+#[derive(
+  Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorJumpTrigger {
+  #[default]
+  MonitorFocus,
+  WindowFocus,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct KeybindingConfig {
+  /// Keyboard shortcut to trigger the keybinding.
+  pub bindings: Vec<String>,
+
+  /// WM commands to run when the keybinding is triggered.
+  #[serde(deserialize_with = "deserialize_command_list")]
+  #[schemars(with = "Vec<InvokeCommand>")]
+  pub commands: Vec<ChainedCommand>,
+
+  /// Whether to repeat the keybinding for each workspace in the config.
+  /// `$workspace` and `$workspace_index` (1-based) in the bindings and
+  /// commands are replaced with the name and position of each
+  /// workspace.
+  ///
+  /// Templates are expanded when the config is read, so this is always
+  /// `false` once parsed.
+  #[serde(default, skip_serializing)]
+  pub for_each_workspace: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct WindowBehaviorConfig {
+  /// New windows are created in this state whenever possible.
+  #[serde(default)]
+  pub initial_state: InitialWindowState,
+
+  /// Sets the default options for when a new window is created. This also
+  /// changes the defaults for when the state change commands, like
+  /// `set_floating`, are used without any flags.
+  pub state_defaults: WindowStateDefaultsConfig,
+}
+
+#[derive(
+  Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InitialWindowState {
+  #[default]
+  Tiling,
+  Floating,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct WindowStateDefaultsConfig {
+  pub floating: FloatingStateConfig,
+  pub fullscreen: FullscreenStateConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FloatingStateConfig {
+  /// Whether to center new floating windows.
+  #[serde(default = "default_bool::<true>")]
+  pub centered: bool,
+
+  /// Whether to show floating windows as always on top.
+  #[serde(default = "default_bool::<false>")]
+  pub shown_on_top: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FullscreenStateConfig {
+  /// Whether to prefer fullscreen windows to be maximized.
+  #[serde(default = "default_bool::<true>")]
+  pub maximized: bool,
+
+  /// Whether to show fullscreen windows as always on top.
+  #[serde(default = "default_bool::<false>")]
+  pub shown_on_top: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct WindowEffectsConfig {
+  /// Visual effects to apply to the focused window.
+  pub focused_window: WindowEffectConfig,
+
+  /// Visual effects to apply to non-focused windows.
+  pub other_windows: WindowEffectConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct WindowEffectConfig {
+  /// Config for optionally applying a colored border.
+  pub border: BorderEffectConfig,
+
+  /// Config for optionally hiding the title bar.
+  #[serde(default)]
+  pub hide_title_bar: HideTitleBarEffectConfig,
+
+  /// Config for optionally changing the corner style.
+  #[serde(default)]
+  pub corner_style: CornerEffectConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct BorderEffectConfig {
+  /// Whether to enable the effect.
+  #[serde(default = "default_bool::<false>")]
+  pub enabled: bool,
+
+  /// Color of the window border.
+  #[serde(default = "default_blue")]
+  pub color: Color,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize, Default)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct HideTitleBarEffectConfig {
+  /// Whether to enable the effect.
+  #[serde(default = "default_bool::<false>")]
+  pub enabled: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct CornerEffectConfig {
+  /// Whether to enable the effect.
+  #[serde(default = "default_bool::<false>")]
+  pub enabled: bool,
+
+  /// Style of the window corners.
+  #[serde(default)]
+  pub style: CornerStyle,
+}
+
+#[derive(
+  Clone,
+  Debug,
+  Deserialize,
+  JsonSchema,
+  Eq,
+  Hash,
+  PartialEq,
+  Serialize,
+  Default,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CornerStyle {
+  #[default]
+  Default,
+  Square,
+  Rounded,
+  SmallRounded,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct WindowRuleConfig {
+  #[serde(deserialize_with = "deserialize_command_list")]
+  #[schemars(with = "Vec<InvokeCommand>")]
+  pub commands: Vec<ChainedCommand>,
+
+  #[serde(rename = "match")]
+  pub match_window: Vec<WindowMatchConfig>,
+
+  #[serde(default = "default_window_rule_on")]
+  pub on: Vec<WindowRuleEvent>,
+
+  #[serde(default = "default_bool::<true>")]
+  pub run_once: bool,
+}
+
+#[derive(
+  Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize,
+)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct WindowMatchConfig {
+  #[serde(default)]
+  pub window_process: Option<MatchType>,
+
+  #[serde(default)]
+  pub window_class: Option<MatchType>,
+
+  #[serde(default)]
+  pub window_title: Option<MatchType>,
+}
+
+impl WindowMatchConfig {
+  /// Whether the given window properties match all of the configured
+  /// match types. Unset match types always match.
+  pub fn is_match(
+    &self,
+    window_process: &str,
+    window_class: &str,
+    window_title: &str,
+  ) -> bool {
+    let is_process_match = self
+      .window_process
+      .as_ref()
+      .map(|match_type| match_type.is_match(window_process))
+      .unwrap_or(true);
+
+    let is_class_match = self
+      .window_class
+      .as_ref()
+      .map(|match_type| match_type.is_match(window_class))
+      .unwrap_or(true);
+
+    let is_title_match = self
+      .window_title
+      .as_ref()
+      .map(|match_type| match_type.is_match(window_title))
+      .unwrap_or(true);
+
+    is_process_match && is_class_match && is_title_match
+  }
+}
+
+/// Due to limitations in `serde_yaml`, we need to use an untagged enum
+/// instead of a regular enum for serialization. Using a regular enum
+/// causes issues with flow-style objects in YAML.
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum MatchType {
+  Equals { equals: String },
+  Includes { includes: String },
+  Regex { regex: String },
+  NotEquals { not_equals: String },
+  NotRegex { not_regex: String },
+}
+
+impl MatchType {
+  /// Whether the given value is a match for the match type.
+  pub fn is_match(&self, value: &str) -> bool {
+    match self {
+      MatchType::Equals { equals } => value == equals,
+      MatchType::Includes { includes } => value.contains(includes),
+      MatchType::Regex { regex } => regex::Regex::new(regex)
+        .map(|re| re.is_match(value))
+        .unwrap_or(false),
+      MatchType::NotEquals { not_equals } => value != not_equals,
+      MatchType::NotRegex { not_regex } => regex::Regex::new(not_regex)
+        .map(|re| !re.is_match(value))
+        .unwrap_or(false),
+    }
+  }
+}
+
+#[derive(
+  Clone, Debug, Deserialize, JsonSchema, Eq, Hash, PartialEq, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowRuleEvent {
+  /// When a window receives native focus.
+  Focus,
+  /// When a window is initially managed.
+  Manage,
+  /// When the title of a window changes.
+  TitleChange,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct WorkspaceConfig {
+  pub name: String,
+  pub display_name: Option<String>,
+  pub bind_to_monitor: Option<MonitorSelector>,
+  #[serde(default = "default_bool::<false>")]
+  pub keep_alive: bool,
+
+  /// Overrides the global `gaps` for the workspace.
+  #[serde(default)]
+  pub gaps: WorkspaceGapsConfig,
+
+  /// Tiling direction of the workspace when it's activated. Defaults to
+  /// vertical on portrait monitors and horizontal otherwise.
+  #[serde(default)]
+  pub tiling_direction: Option<TilingDirection>,
+
+  /// Overrides `window_behavior.initial_state` for new windows on the
+  /// workspace.
+  #[serde(default)]
+  pub initial_window_state: Option<InitialWindowState>,
+
+  /// Overrides `general.focus_follows_cursor` for windows on the
+  /// workspace.
+  #[serde(default)]
+  pub focus_follows_cursor: Option<bool>,
+}
+
+#[derive(
+  Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize,
+)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct WorkspaceGapsConfig {
+  /// Gap between adjacent windows.
+  #[serde(default)]
+  pub inner_gap: Option<LengthValue>,
+
+  /// Gap between windows and the screen edge.
+  #[serde(default)]
+  pub outer_gap: Option<RectDelta>,
+}
+
+/// Selects the monitor that a workspace is bound to.
+///
+/// Uses an untagged enum for the same reason as `MatchType`.
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum MonitorSelector {
+  /// Index of the monitor, where 0 is the leftmost monitor.
+  Index(u32),
+
+  /// Monitor with matching properties (e.g. its hardware ID).
+  Match(MonitorMatchConfig),
+
+  /// Selectors to try in order. The first selector that matches a
+  /// connected monitor is used.
+  Fallback(Vec<MonitorSelector>),
+}
+
+/// Properties to match a monitor by. At least one property needs to be
+/// set.
+#[derive(Clone, Debug, Default, JsonSchema, PartialEq, Serialize)]
+#[serde(deny_unknown_fields, rename_all(serialize = "camelCase"))]
+#[schemars(extend("minProperties" = 1))]
+pub struct MonitorMatchConfig {
+  #[serde(default)]
+  pub device_name: Option<MatchType>,
+
+  #[serde(default)]
+  pub device_path: Option<MatchType>,
+
+  #[serde(default)]
+  pub hardware_id: Option<MatchType>,
+}
+
+/// Deserialize a `MonitorMatchConfig`, rejecting an empty match (e.g.
+/// `bind_to_monitor: {}`), which would otherwise match any monitor.
+impl<'de> Deserialize<'de> for MonitorMatchConfig {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct MonitorMatchConfigDe {
+      #[serde(default)]
+      device_name: Option<MatchType>,
+
+      #[serde(default)]
+      device_path: Option<MatchType>,
+
+      #[serde(default)]
+      hardware_id: Option<MatchType>,
+    }
+
+    let config = MonitorMatchConfigDe::deserialize(deserializer)?;
+
+    if config.device_name.is_none()
+      && config.device_path.is_none()
+      && config.hardware_id.is_none()
+    {
+      return Err(serde::de::Error::custom(
+        "Monitor match needs at least one of `device_name`, `device_path` or `hardware_id`.",
+      ));
+    }
+
+    Ok(Self {
+      device_name: config.device_name,
+      device_path: config.device_path,
+      hardware_id: config.hardware_id,
+    })
+  }
+}
+
+impl MonitorMatchConfig {
+  /// Whether the given monitor properties match all of the configured
+  /// match types. Unset match types always match.
+  pub fn is_match(
+    &self,
+    device_name: Option<&str>,
+    device_path: Option<&str>,
+    hardware_id: Option<&str>,
+  ) -> bool {
+    is_property_match(&self.device_name, device_name)
+      && is_property_match(&self.device_path, device_path)
+      && is_property_match(&self.hardware_id, hardware_id)
+  }
+}
+
+/// Whether a monitor property matches the match type. Unavailable
+/// properties only match if no match type is set.
+fn is_property_match(
+  match_type: &Option<MatchType>,
+  value: Option<&str>,
+) -> bool {
+  match (match_type, value) {
+    (None, _) => true,
+    (Some(match_type), Some(value)) => match_type.is_match(value),
+    (Some(_), None) => false,
+  }
+}
+
+/// Helper function for setting a default value for a boolean field.
+pub(crate) const fn default_bool<const V: bool>() -> bool {
+  V
+}
+
+/// Helper function for setting a default value for a color field.
+const fn default_blue() -> Color {
+  Color {
+    r: 140,
+    g: 190,
+    b: 255,
+    a: 255,
+  }
+}
+
+/// Helper function for setting a default value for window rule events.
+fn default_window_rule_on() -> Vec<WindowRuleEvent> {
+  vec![WindowRuleEvent::Manage, WindowRuleEvent::TitleChange]
+}
+
+impl Default for CornerEffectConfig {
+  fn default() -> Self {
+    CornerEffectConfig {
+      enabled: false,
+      style: CornerStyle::Default,
+    }
+  }
+}
@@ -1,8 +1,11 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::{Direction, LengthValue, Point, RectDelta};
+use crate::{Direction, LengthValue, Point, RectDelta};
 
-#[derive(Debug, Deserialize, Clone, Serialize, Eq, PartialEq)]
+#[derive(
+  Debug, Deserialize, JsonSchema, Clone, Serialize, Eq, PartialEq,
+)]
 pub struct Rect {
   /// X-coordinate of the left edge of the rectangle.
   pub left: i32,
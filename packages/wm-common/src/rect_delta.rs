@@ -1,8 +1,9 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::LengthValue;
+use crate::LengthValue;
 
-#[derive(Debug, Deserialize, Clone, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema, Clone, PartialEq, Serialize)]
 pub struct RectDelta {
   /// The delta in x-coordinates on the left of the rectangle.
   pub left: LengthValue,
@@ -0,0 +1,35 @@
+use schemars::{
+  generate::SchemaSettings, JsonSchema, Schema, SchemaGenerator,
+};
+use serde::Serialize;
+
+use crate::{ClientMessage, ParsedConfig, ServerMessage};
+
+/// Any message that is sent to or from the IPC server.
+///
+/// Only used as the root type of the IPC schema, so it's never
+/// constructed.
+#[allow(dead_code)]
+#[derive(JsonSchema, Serialize)]
+#[serde(untagged)]
+enum IpcMessage {
+  Client(ClientMessage),
+  Server(ServerMessage),
+}
+
+/// Gets the JSON schema of the user config file (`config.yaml`).
+///
+/// Field names are as written in the config file (e.g.
+/// `focus_follows_cursor`).
+pub fn config_schema() -> Schema {
+  SchemaGenerator::new(SchemaSettings::draft07().for_deserialize())
+    .into_root_schema_for::<ParsedConfig>()
+}
+
+/// Gets the JSON schema of the messages sent to and from the IPC server.
+///
+/// Field names are as serialized over IPC (e.g. `focusFollowsCursor`).
+pub fn ipc_schema() -> Schema {
+  SchemaGenerator::new(SchemaSettings::draft07().for_serialize())
+    .into_root_schema_for::<IpcMessage>()
+}
@@ -1,11 +1,12 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use anyhow::bail;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::Direction;
+use crate::Direction;
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum TilingDirection {
   Vertical,
@@ -17,7 +18,7 @@ impl TilingDirection {
   ///
   /// Example:
   /// ```
-  /// # use wm::common::TilingDirection;
+  /// # use wm_common::TilingDirection;
   /// let dir = TilingDirection::Horizontal.inverse();
   /// assert_eq!(dir, TilingDirection::Vertical);
   /// ```
@@ -33,7 +34,7 @@ impl TilingDirection {
   ///
   /// Example:
   /// ```
-  /// # use wm::common::{Direction, TilingDirection};
+  /// # use wm_common::{Direction, TilingDirection};
   /// let dir = TilingDirection::from_direction(&Direction::Left);
   /// assert_eq!(dir, TilingDirection::Horizontal);
   /// ```
@@ -52,7 +53,7 @@ impl FromStr for TilingDirection {
   ///
   /// Example:
   /// ```
-  /// # use wm::common::TilingDirection;
+  /// # use wm_common::TilingDirection;
   /// # use std::str::FromStr;
   /// let dir = TilingDirection::from_str("horizontal");
   /// assert_eq!(dir.unwrap(), TilingDirection::Horizontal);
@@ -68,3 +69,12 @@ impl FromStr for TilingDirection {
     }
   }
 }
+
+impl fmt::Display for TilingDirection {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      TilingDirection::Horizontal => write!(f, "horizontal"),
+      TilingDirection::Vertical => write!(f, "vertical"),
+    }
+  }
+}
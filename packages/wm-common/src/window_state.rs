@@ -0,0 +1,49 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  FloatingStateConfig, FullscreenStateConfig, InitialWindowState,
+  ParsedConfig, WorkspaceConfig,
+};
+
+/// Represents the possible states a window can have.
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WindowState {
+  Floating(FloatingStateConfig),
+  Fullscreen(FullscreenStateConfig),
+  Minimized,
+  Tiling,
+}
+
+impl WindowState {
+  pub fn default_from_config(config: &ParsedConfig) -> Self {
+    Self::from_initial_state(&config.window_behavior.initial_state, config)
+  }
+
+  /// Default state for new windows on the given workspace. Uses the
+  /// workspace's `initial_window_state` if set.
+  pub fn default_for_workspace(
+    config: &ParsedConfig,
+    workspace_config: &WorkspaceConfig,
+  ) -> Self {
+    let initial_state = workspace_config
+      .initial_window_state
+      .as_ref()
+      .unwrap_or(&config.window_behavior.initial_state);
+
+    Self::from_initial_state(initial_state, config)
+  }
+
+  fn from_initial_state(
+    initial_state: &InitialWindowState,
+    config: &ParsedConfig,
+  ) -> Self {
+    match initial_state {
+      InitialWindowState::Tiling => WindowState::Tiling,
+      InitialWindowState::Floating => WindowState::Floating(
+        config.window_behavior.state_defaults.floating.clone(),
+      ),
+    }
+  }
+}
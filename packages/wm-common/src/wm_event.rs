@@ -0,0 +1,127 @@
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+  BindingModeConfig, ContainerDto, ParsedConfig, TilingDirection,
+};
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(
+  tag = "eventType",
+  rename_all = "snake_case",
+  rename_all_fields = "camelCase"
+)]
+pub enum WmEvent {
+  ApplicationExiting,
+  BindingModesChanged {
+    new_binding_modes: Vec<BindingModeConfig>,
+  },
+  FocusChanged {
+    focused_container: ContainerDto,
+  },
+  FocusedContainerMoved {
+    focused_container: ContainerDto,
+  },
+  MonitorAdded {
+    added_monitor: ContainerDto,
+  },
+  MonitorRemoved {
+    removed_id: Uuid,
+    removed_device_name: String,
+  },
+  MonitorUpdated {
+    updated_monitor: ContainerDto,
+  },
+  TilingDirectionChanged {
+    direction_container: ContainerDto,
+    new_tiling_direction: TilingDirection,
+  },
+  UserConfigChanged {
+    config_path: String,
+    config_string: String,
+    parsed_config: Box<ParsedConfig>,
+  },
+  UserConfigReloadFailed {
+    config_path: String,
+    error: String,
+  },
+  WindowManaged {
+    managed_window: ContainerDto,
+  },
+  WindowUnmanaged {
+    unmanaged_id: Uuid,
+    unmanaged_handle: isize,
+  },
+  WorkspaceActivated {
+    activated_workspace: ContainerDto,
+  },
+  WorkspaceDeactivated {
+    deactivated_id: Uuid,
+    deactivated_name: String,
+  },
+  WorkspaceUpdated {
+    updated_workspace: ContainerDto,
+  },
+}
+
+impl WmEvent {
+  /// Container that the event is about, if the event carries one.
+  pub fn container(&self) -> Option<&ContainerDto> {
+    match self {
+      WmEvent::FocusChanged { focused_container }
+      | WmEvent::FocusedContainerMoved { focused_container } => {
+        Some(focused_container)
+      }
+      WmEvent::MonitorAdded { added_monitor } => Some(added_monitor),
+      WmEvent::MonitorUpdated { updated_monitor } => Some(updated_monitor),
+      WmEvent::TilingDirectionChanged {
+        direction_container,
+        ..
+      } => Some(direction_container),
+      WmEvent::WindowManaged { managed_window } => Some(managed_window),
+      WmEvent::WorkspaceActivated {
+        activated_workspace,
+      } => Some(activated_workspace),
+      WmEvent::WorkspaceUpdated { updated_workspace } => {
+        Some(updated_workspace)
+      }
+      _ => None,
+    }
+  }
+
+  /// ID of the container that the event is about. Unlike `container`,
+  /// this includes containers that have been removed.
+  pub fn container_id(&self) -> Option<Uuid> {
+    match self {
+      WmEvent::MonitorRemoved { removed_id, .. } => Some(*removed_id),
+      WmEvent::WindowUnmanaged { unmanaged_id, .. } => Some(*unmanaged_id),
+      WmEvent::WorkspaceDeactivated { deactivated_id, .. } => {
+        Some(*deactivated_id)
+      }
+      _ => self.container().map(|container| container.id()),
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, ValueEnum)]
+#[clap(rename_all = "snake_case")]
+pub enum SubscribableEvent {
+  All,
+  ApplicationExiting,
+  BindingModesChanged,
+  FocusChanged,
+  FocusedContainerMoved,
+  MonitorAdded,
+  MonitorUpdated,
+  MonitorRemoved,
+  TilingDirectionChanged,
+  UserConfigChanged,
+  UserConfigReloadFailed,
+  WindowManaged,
+  WindowUnmanaged,
+  WorkspaceActivated,
+  WorkspaceDeactivated,
+  WorkspaceUpdated,
+}
@@ -0,0 +1,817 @@
+use std::{fmt, time::Duration};
+
+use anyhow::{bail, Context};
+use clap::ValueEnum;
+use futures_util::{
+  stream::{self, BoxStream},
+  FutureExt, SinkExt, StreamExt,
+};
+use tokio::time;
+use tokio_tungstenite::{
+  client_async,
+  tungstenite::{
+    client::IntoClientRequest,
+    http::header::AUTHORIZATION,
+    protocol::{frame::coding::CloseCode, CloseFrame},
+    Message,
+  },
+  WebSocketStream,
+};
+use tracing::warn;
+use uuid::Uuid;
+use wm_common::{
+  AppMetadataData, BindingModeConfig, CapabilitiesData, ClientMessage,
+  ClientResponseData, ClientResponseMessage, CommandBatch,
+  CommandBatchData, ContainerDto, EventSubscriptionMessage, InvokeCommand,
+  IpcConfig, IpcFeature, MonitorDto, ServerMessage, SubscribableEvent,
+  WindowDto, WmEvent, WorkspaceDto, IPC_PROTOCOL_VERSION,
+};
+
+use crate::transport::{self, IpcStream};
+
+/// Websocket connection to the IPC server.
+type IpcConnection = WebSocketStream<Box<dyn IpcStream>>;
+
+/// Stream of WM events returned by `IpcClient::subscribe`.
+pub type EventStream = BoxStream<'static, anyhow::Result<WmEvent>>;
+
+/// Options for connecting to the IPC server and waiting for responses.
+#[derive(Clone, Debug)]
+pub struct IpcClientOptions {
+  /// Max time to wait for a connection to be established.
+  pub connect_timeout: Duration,
+
+  /// Max time to wait for the response to a request.
+  pub request_timeout: Duration,
+
+  /// Number of times to try re-establishing a lost connection. Set to 0
+  /// to disable reconnection.
+  pub reconnect_attempts: u32,
+
+  /// Delay before each reconnection attempt.
+  pub reconnect_delay: Duration,
+}
+
+impl Default for IpcClientOptions {
+  fn default() -> Self {
+    Self {
+      connect_timeout: Duration::from_secs(5),
+      request_timeout: Duration::from_secs(5),
+      reconnect_attempts: 5,
+      reconnect_delay: Duration::from_millis(500),
+    }
+  }
+}
+
+/// Error yielded by an `EventStream` when events were missed, either
+/// because the subscriber fell behind or because the connection was
+/// re-established.
+///
+/// Consumers that mirror the WM state should re-query it.
+#[derive(Clone, Debug)]
+pub struct EventsMissed {
+  /// Number of missed events, if known.
+  pub count: Option<u64>,
+}
+
+impl fmt::Display for EventsMissed {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.count {
+      Some(count) => write!(f, "Missed {} events.", count),
+      None => write!(f, "Missed events while reconnecting."),
+    }
+  }
+}
+
+impl std::error::Error for EventsMissed {}
+
+/// Error returned when the IPC server closes the connection with a close
+/// frame.
+#[derive(Clone, Debug)]
+pub struct ConnectionClosed {
+  /// Close code sent by the server, if any.
+  pub code: Option<u16>,
+
+  /// Reason sent by the server (e.g. `WM is shutting down.`).
+  pub reason: String,
+}
+
+impl ConnectionClosed {
+  fn from_frame(frame: Option<CloseFrame>) -> Self {
+    match frame {
+      Some(frame) => Self {
+        code: Some(frame.code.into()),
+        reason: frame.reason.into_owned(),
+      },
+      None => Self {
+        code: None,
+        reason: String::new(),
+      },
+    }
+  }
+
+  /// Whether the connection was closed because the WM is shutting down.
+  pub fn is_shutdown(&self) -> bool {
+    self.code == Some(CloseCode::Away.into())
+  }
+}
+
+impl fmt::Display for ConnectionClosed {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.reason.is_empty() {
+      true => write!(f, "IPC connection closed by server."),
+      false => {
+        write!(f, "IPC connection closed by server: {}", self.reason)
+      }
+    }
+  }
+}
+
+impl std::error::Error for ConnectionClosed {}
+
+pub struct IpcClient {
+  ipc_config: IpcConfig,
+  options: IpcClientOptions,
+  /// Current connection. `None` if the connection was lost, in which
+  /// case it's re-established on the next request.
+  connection: Option<IpcConnection>,
+  /// Capabilities of the server. Negotiated on first use, and again
+  /// after reconnecting.
+  capabilities: Option<CapabilitiesData>,
+}
+
+impl IpcClient {
+  /// Connects to the IPC server over the configured transport.
+  pub async fn connect(ipc_config: &IpcConfig) -> anyhow::Result<Self> {
+    Self::connect_with_options(ipc_config, IpcClientOptions::default())
+      .await
+  }
+
+  /// Connects to the IPC server with custom timeouts and reconnection
+  /// behavior.
+  ///
+  /// Capabilities are negotiated before the first typed request (e.g.
+  /// `query_windows` or `run_commands`), which fails if the server uses
+  /// an incompatible protocol version. Raw requests via `request` skip
+  /// the negotiation.
+  pub async fn connect_with_options(
+    ipc_config: &IpcConfig,
+    options: IpcClientOptions,
+  ) -> anyhow::Result<Self> {
+    let connection = open_connection(ipc_config, &options).await?;
+
+    Ok(Self {
+      ipc_config: ipc_config.clone(),
+      options,
+      connection: Some(connection),
+      capabilities: None,
+    })
+  }
+
+  /// Capabilities of the server. Queried on first use.
+  ///
+  /// Fails if the server uses an incompatible protocol version.
+  pub async fn capabilities(
+    &mut self,
+  ) -> anyhow::Result<&CapabilitiesData> {
+    if self.capabilities.is_none() {
+      let command = "query capabilities";
+      let response = self.request(command).await?;
+      self.capabilities = Some(negotiate_capabilities(response, command)?);
+    }
+
+    // Safety: Capabilities are set above.
+    Ok(self.capabilities.as_ref().unwrap())
+  }
+
+  /// Outputs the IPC protocol version, along with the supported queries,
+  /// events and features.
+  ///
+  /// Unlike `capabilities`, this doesn't check that the protocol version
+  /// is compatible.
+  pub async fn query_capabilities(
+    &mut self,
+  ) -> anyhow::Result<CapabilitiesData> {
+    let command = "query capabilities";
+    let response = self.request(command).await?;
+
+    match Self::response_data(response, command)? {
+      ClientResponseData::Capabilities(data) => Ok(data),
+      _ => bail!("Invalid data in capabilities query response."),
+    }
+  }
+
+  /// Outputs metadata about the application (e.g. version number).
+  pub async fn query_app_metadata(
+    &mut self,
+  ) -> anyhow::Result<AppMetadataData> {
+    match self.request_data("query app-metadata").await? {
+      ClientResponseData::AppMetadata(data) => Ok(data),
+      _ => bail!("Invalid data in app metadata query response."),
+    }
+  }
+
+  /// Gets the active binding modes.
+  pub async fn query_binding_modes(
+    &mut self,
+  ) -> anyhow::Result<Vec<BindingModeConfig>> {
+    match self.request_data("query binding-modes").await? {
+      ClientResponseData::BindingModes(data) => Ok(data.binding_modes),
+      _ => bail!("Invalid data in binding modes query response."),
+    }
+  }
+
+  /// Gets the focused container (either a window or an empty workspace).
+  pub async fn query_focused(&mut self) -> anyhow::Result<ContainerDto> {
+    match self.request_data("query focused").await? {
+      ClientResponseData::Focused(data) => Ok(data.focused),
+      _ => bail!("Invalid data in focused query response."),
+    }
+  }
+
+  /// Gets all monitors.
+  pub async fn query_monitors(
+    &mut self,
+  ) -> anyhow::Result<Vec<MonitorDto>> {
+    match self.request_data("query monitors").await? {
+      ClientResponseData::Monitors(data) => Ok(
+        data
+          .monitors
+          .into_iter()
+          .filter_map(|container| match container {
+            ContainerDto::Monitor(monitor) => Some(monitor),
+            _ => None,
+          })
+          .collect(),
+      ),
+      _ => bail!("Invalid data in monitors query response."),
+    }
+  }
+
+  /// Gets all windows.
+  pub async fn query_windows(&mut self) -> anyhow::Result<Vec<WindowDto>> {
+    match self.request_data("query windows").await? {
+      ClientResponseData::Windows(data) => Ok(
+        data
+          .windows
+          .into_iter()
+          .filter_map(|container| match container {
+            ContainerDto::Window(window) => Some(window),
+            _ => None,
+          })
+          .collect(),
+      ),
+      _ => bail!("Invalid data in windows query response."),
+    }
+  }
+
+  /// Gets all active workspaces.
+  pub async fn query_workspaces(
+    &mut self,
+  ) -> anyhow::Result<Vec<WorkspaceDto>> {
+    match self.request_data("query workspaces").await? {
+      ClientResponseData::Workspaces(data) => Ok(
+        data
+          .workspaces
+          .into_iter()
+          .filter_map(|container| match container {
+            ContainerDto::Workspace(workspace) => Some(workspace),
+            _ => None,
+          })
+          .collect(),
+      ),
+      _ => bail!("Invalid data in workspaces query response."),
+    }
+  }
+
+  /// Gets the full container tree, starting from the root container.
+  pub async fn query_tree(&mut self) -> anyhow::Result<ContainerDto> {
+    match self.request_data("query tree").await? {
+      ClientResponseData::Tree(data) => Ok(data.root),
+      _ => bail!("Invalid data in tree query response."),
+    }
+  }
+
+  /// Gets a single container (and its descendants) by its ID.
+  pub async fn query_container(
+    &mut self,
+    container_id: Uuid,
+  ) -> anyhow::Result<ContainerDto> {
+    let command = format!("query container --id {}", container_id);
+
+    match self.request_data(&command).await? {
+      ClientResponseData::Container(data) => Ok(data.container),
+      _ => bail!("Invalid data in container query response."),
+    }
+  }
+
+  /// Runs a WM command on the focused container.
+  ///
+  /// Returns the ID of the subject container after the command has run.
+  pub async fn run_command(
+    &mut self,
+    command: InvokeCommand,
+  ) -> anyhow::Result<Uuid> {
+    match self.request_data(&format!("command {}", command)).await? {
+      ClientResponseData::Command(data) => Ok(data.subject_container_id),
+      _ => bail!("Invalid data in command response."),
+    }
+  }
+
+  /// Runs multiple WM commands in order with a single redraw at the end.
+  ///
+  /// Uses the focused container as the subject if no container ID is
+  /// given. Commands after a failing command are skipped, and the result
+  /// of each command is included in the returned data.
+  pub async fn run_commands(
+    &mut self,
+    commands: Vec<InvokeCommand>,
+    subject_container_id: Option<Uuid>,
+  ) -> anyhow::Result<CommandBatchData> {
+    if !self
+      .capabilities()
+      .await?
+      .has_feature(IpcFeature::CommandBatch)
+    {
+      bail!("IPC server doesn't support command batches.");
+    }
+
+    let client_message = ClientMessage {
+      id: Some(Uuid::new_v4().to_string().into()),
+      command: String::new(),
+      batch: Some(CommandBatch {
+        commands: commands.iter().map(ToString::to_string).collect(),
+        subject_container_id,
+      }),
+      token: None,
+    };
+
+    let response = self.send_message(client_message).await?;
+
+    match Self::response_data(response, "command batch")? {
+      ClientResponseData::CommandBatch(data) => Ok(data),
+      _ => bail!("Invalid data in command batch response."),
+    }
+  }
+
+  /// Subscribes to the given WM events.
+  ///
+  /// The subscription uses a separate connection, so the client can still
+  /// be used while the stream is consumed. If the connection is lost, it
+  /// is re-established and an `EventsMissed` error is yielded. The
+  /// stream ends once reconnection fails.
+  pub async fn subscribe(
+    &mut self,
+    events: &[SubscribableEvent],
+  ) -> anyhow::Result<EventStream> {
+    let command = subscribe_command(events, self.capabilities().await?)?;
+
+    let mut connection =
+      open_connection(&self.ipc_config, &self.options).await?;
+
+    let subscription_id =
+      send_subscribe(&mut connection, &command, &self.options).await?;
+
+    let subscription = EventSubscription {
+      ipc_config: self.ipc_config.clone(),
+      options: self.options.clone(),
+      command,
+      connection: Some(connection),
+      subscription_id,
+      is_closed: false,
+    };
+
+    let event_stream =
+      stream::unfold(subscription, |mut subscription| async move {
+        let event = subscription.next_event().await?;
+        Some((event, subscription))
+      });
+
+    Ok(event_stream.boxed())
+  }
+
+  /// Sends a raw command string (e.g. `query windows`) and waits for its
+  /// response.
+  ///
+  /// The connection is re-established first if it was lost. Requests are
+  /// never resent, since the server might have already handled them.
+  pub async fn request(
+    &mut self,
+    command: &str,
+  ) -> anyhow::Result<ClientResponseMessage> {
+    let client_message = ClientMessage {
+      id: Some(Uuid::new_v4().to_string().into()),
+      command: command.to_string(),
+      batch: None,
+      token: None,
+    };
+
+    self.send_message(client_message).await
+  }
+
+  /// Waits for the next message of a subscription made via `request`.
+  ///
+  /// Prefer `subscribe` unless the raw messages are needed. Subscription
+  /// messages that haven't been read are discarded on the next request.
+  pub async fn next_event_message(
+    &mut self,
+    subscription_id: &Uuid,
+  ) -> anyhow::Result<EventSubscriptionMessage> {
+    let connection = self
+      .connection
+      .as_mut()
+      .context("Not connected to IPC server.")?;
+
+    loop {
+      let message = next_server_message(connection).await;
+
+      match message {
+        Ok(ServerMessage::EventSubscription(message))
+          if &message.subscription_id == subscription_id =>
+        {
+          return Ok(message);
+        }
+        Ok(_) => continue,
+        Err(err) => {
+          self.connection = None;
+          return Err(err);
+        }
+      }
+    }
+  }
+
+  /// Sends a request and returns the response data, or an error if the
+  /// server failed to handle it.
+  ///
+  /// Capabilities are negotiated first, so that an incompatible server
+  /// is refused rather than failing to deserialize its response.
+  async fn request_data(
+    &mut self,
+    command: &str,
+  ) -> anyhow::Result<ClientResponseData> {
+    self.capabilities().await?;
+
+    let response = self.request(command).await?;
+    Self::response_data(response, command)
+  }
+
+  fn response_data(
+    response: ClientResponseMessage,
+    description: &str,
+  ) -> anyhow::Result<ClientResponseData> {
+    if !response.success {
+      bail!(response
+        .error
+        .unwrap_or_else(|| format!("Request failed: '{}'.", description)));
+    }
+
+    response.data.with_context(|| {
+      format!("No data in response to '{}'.", description)
+    })
+  }
+
+  async fn send_message(
+    &mut self,
+    client_message: ClientMessage,
+  ) -> anyhow::Result<ClientResponseMessage> {
+    // Handle messages received since the last request, so that a
+    // connection that was closed by the server (e.g. due to an idle
+    // timeout) is re-established before sending.
+    if let Some(connection) = self.connection.as_mut() {
+      if !process_pending(connection) {
+        self.connection = None;
+      }
+    }
+
+    // The server might have been upgraded in the meantime, so its
+    // capabilities are renegotiated on next use after reconnecting.
+    if self.connection.is_none() {
+      let connection = reconnect(&self.ipc_config, &self.options).await?;
+
+      self.capabilities = None;
+      self.connection = Some(connection);
+    }
+
+    let connection = self
+      .connection
+      .as_mut()
+      .context("Not connected to IPC server.")?;
+
+    let response = match time::timeout(
+      self.options.request_timeout,
+      send_and_wait(connection, &client_message),
+    )
+    .await
+    {
+      Ok(response) => response,
+      Err(_) => {
+        // The server might be unresponsive, so the connection is closed
+        // and re-established on the next request.
+        if let Some(mut connection) = self.connection.take() {
+          let _ = time::timeout(
+            self.options.connect_timeout,
+            connection.close(None),
+          )
+          .await;
+        }
+
+        bail!("Timed out waiting for response from IPC server.");
+      }
+    };
+
+    // Drop the connection on transport errors, so that it's
+    // re-established on the next request.
+    if response.is_err() {
+      self.connection = None;
+    }
+
+    response
+  }
+}
+
+/// State of an event subscription created via `IpcClient::subscribe`.
+struct EventSubscription {
+  ipc_config: IpcConfig,
+  options: IpcClientOptions,
+  /// Subscribe command that is resent on reconnection (e.g.
+  /// `sub -e window_managed`).
+  command: String,
+  connection: Option<IpcConnection>,
+  subscription_id: Uuid,
+  is_closed: bool,
+}
+
+impl EventSubscription {
+  /// Waits for the next event. Returns `None` once the connection is lost
+  /// and can't be re-established.
+  async fn next_event(&mut self) -> Option<anyhow::Result<WmEvent>> {
+    if self.is_closed {
+      return None;
+    }
+
+    loop {
+      let Some(connection) = self.connection.as_mut() else {
+        // Events emitted while disconnected are lost, so consumers are
+        // told to re-sync once the subscription has been renewed.
+        return Some(match self.resubscribe().await {
+          Ok(_) => Err(EventsMissed { count: None }.into()),
+          Err(err) => {
+            self.is_closed = true;
+            Err(err)
+          }
+        });
+      };
+
+      match next_server_message(connection).await {
+        Ok(ServerMessage::EventSubscription(message))
+          if message.subscription_id == self.subscription_id =>
+        {
+          if let Some(count) = message.lagged_count {
+            return Some(Err(EventsMissed { count: Some(count) }.into()));
+          }
+
+          return Some(message.data.context(
+            "Received event subscription message without data.",
+          ));
+        }
+        Ok(_) => continue,
+        Err(err) => {
+          self.connection = None;
+
+          if self.options.reconnect_attempts == 0 {
+            self.is_closed = true;
+            return Some(Err(err));
+          }
+
+          warn!("Lost connection to IPC server: {:?}", err);
+        }
+      }
+    }
+  }
+
+  /// Re-establishes the connection and renews the subscription.
+  async fn resubscribe(&mut self) -> anyhow::Result<()> {
+    let mut connection =
+      reconnect(&self.ipc_config, &self.options).await?;
+
+    self.subscription_id =
+      send_subscribe(&mut connection, &self.command, &self.options)
+        .await?;
+    self.connection = Some(connection);
+
+    Ok(())
+  }
+}
+
+/// Opens a websocket connection to the IPC server.
+///
+/// The auth token written by the WM is sent in the handshake if one
+/// exists for the given port.
+async fn open_connection(
+  ipc_config: &IpcConfig,
+  options: &IpcClientOptions,
+) -> anyhow::Result<IpcConnection> {
+  let connect = async {
+    let (transport_stream, server_url) =
+      transport::connect(ipc_config).await?;
+
+    let mut request = server_url.into_client_request()?;
+
+    if let Some(token) = ipc_config.read_auth_token() {
+      request
+        .headers_mut()
+        .insert(AUTHORIZATION, format!("Bearer {}", token).parse()?);
+    }
+
+    let (stream, _) = client_async(request, transport_stream).await?;
+    anyhow::Ok(stream)
+  };
+
+  time::timeout(options.connect_timeout, connect)
+    .await
+    .context("Timed out connecting to IPC server.")?
+    .context("Failed to connect to IPC server.")
+}
+
+/// Gets the server's capabilities from the response to a capabilities
+/// query, and checks that its protocol version is compatible with this
+/// client.
+fn negotiate_capabilities(
+  response: ClientResponseMessage,
+  command: &str,
+) -> anyhow::Result<CapabilitiesData> {
+  // Servers that predate protocol versioning reject the query.
+  let capabilities = match IpcClient::response_data(response, command) {
+    Ok(ClientResponseData::Capabilities(data)) => data,
+    _ => bail!(
+      "IPC server doesn't support protocol version negotiation. It's \
+      likely running an older version of GlazeWM that is incompatible \
+      with this client (protocol version {}).",
+      IPC_PROTOCOL_VERSION
+    ),
+  };
+
+  if capabilities.protocol_version != IPC_PROTOCOL_VERSION {
+    bail!(
+      "IPC server (v{}) uses protocol version {}, which is incompatible \
+      with this client (protocol version {}).",
+      capabilities.version,
+      capabilities.protocol_version,
+      IPC_PROTOCOL_VERSION
+    );
+  }
+
+  Ok(capabilities)
+}
+
+/// Re-establishes a lost connection, retrying up to the configured number
+/// of attempts.
+async fn reconnect(
+  ipc_config: &IpcConfig,
+  options: &IpcClientOptions,
+) -> anyhow::Result<IpcConnection> {
+  if options.reconnect_attempts == 0 {
+    bail!("Lost connection to IPC server.");
+  }
+
+  let mut attempt = 0;
+
+  loop {
+    attempt += 1;
+    time::sleep(options.reconnect_delay).await;
+
+    match open_connection(ipc_config, options).await {
+      Ok(connection) => return Ok(connection),
+      Err(err) if attempt < options.reconnect_attempts => {
+        warn!("Reconnection attempt {} failed: {:?}", attempt, err);
+      }
+      Err(err) => {
+        return Err(err.context(format!(
+          "Failed to reconnect after {} attempts.",
+          attempt
+        )));
+      }
+    }
+  }
+}
+
+/// Sends a subscribe command and returns the ID of the subscription.
+async fn send_subscribe(
+  connection: &mut IpcConnection,
+  command: &str,
+  options: &IpcClientOptions,
+) -> anyhow::Result<Uuid> {
+  let client_message = ClientMessage {
+    id: Some(Uuid::new_v4().to_string().into()),
+    command: command.to_string(),
+    batch: None,
+    token: None,
+  };
+
+  let response = time::timeout(
+    options.request_timeout,
+    send_and_wait(connection, &client_message),
+  )
+  .await
+  .context("Timed out waiting for response from IPC server.")??;
+
+  match IpcClient::response_data(response, command)? {
+    ClientResponseData::EventSubscribe(data) => Ok(data.subscription_id),
+    _ => bail!("No subscription ID in event subscription response."),
+  }
+}
+
+/// Sends a message and waits for the response with a matching ID.
+async fn send_and_wait(
+  connection: &mut IpcConnection,
+  client_message: &ClientMessage,
+) -> anyhow::Result<ClientResponseMessage> {
+  connection
+    .send(Message::Text(serde_json::to_string(client_message)?))
+    .await
+    .context("Failed to send message to IPC server.")?;
+
+  loop {
+    if let ServerMessage::ClientResponse(response) =
+      next_server_message(connection).await?
+    {
+      if response.client_message_id == client_message.id {
+        return Ok(response);
+      }
+    }
+  }
+}
+
+/// Waits for the next message from the IPC server.
+async fn next_server_message(
+  connection: &mut IpcConnection,
+) -> anyhow::Result<ServerMessage> {
+  loop {
+    let message = connection
+      .next()
+      .await
+      .context("IPC connection closed.")?
+      .context("Failed to receive message from IPC server.")?;
+
+    match message {
+      Message::Text(text) => return Ok(serde_json::from_str(&text)?),
+      Message::Close(frame) => {
+        return Err(ConnectionClosed::from_frame(frame).into())
+      }
+      // Skip control frames (e.g. pings).
+      _ => continue,
+    }
+  }
+}
+
+/// Reads messages that have already been received without waiting for
+/// new ones. This also answers pings from the server.
+///
+/// Stale responses (e.g. to timed out requests) are discarded. Returns
+/// `false` if the connection has been closed.
+fn process_pending(connection: &mut IpcConnection) -> bool {
+  while let Some(message) = connection.next().now_or_never() {
+    match message {
+      Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return false,
+      Some(Ok(_)) => continue,
+    }
+  }
+
+  true
+}
+
+/// Gets the subscribe command for the given events (e.g.
+/// `sub -e window_managed window_unmanaged`).
+///
+/// Fails if any of the events aren't supported by the server.
+fn subscribe_command(
+  events: &[SubscribableEvent],
+  capabilities: &CapabilitiesData,
+) -> anyhow::Result<String> {
+  if events.is_empty() {
+    bail!("At least one event is required to subscribe.");
+  }
+
+  let event_names = events
+    .iter()
+    .map(|event| {
+      event
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .context("Invalid event to subscribe to.")
+    })
+    .collect::<anyhow::Result<Vec<_>>>()?;
+
+  if let Some(event_name) = event_names
+    .iter()
+    .find(|name| !capabilities.has_event(name))
+  {
+    bail!(
+      "IPC server (v{}) doesn't support the '{}' event.",
+      capabilities.version,
+      event_name
+    );
+  }
+
+  Ok(format!("sub -e {}", event_names.join(" ")))
+}
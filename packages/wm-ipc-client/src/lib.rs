@@ -0,0 +1,11 @@
+mod ipc_client;
+mod listener;
+#[cfg(windows)]
+mod security;
+mod transport;
+
+pub use ipc_client::*;
+pub use listener::{IpcListener, PrefixedStream};
+#[cfg(windows)]
+pub use security::PrivateSecurityDescriptor;
+pub use transport::IpcStream;
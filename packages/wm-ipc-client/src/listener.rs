@@ -0,0 +1,300 @@
+#[cfg(windows)]
+use std::ffi::c_void;
+#[cfg(unix)]
+use std::{
+  fs,
+  os::unix::fs::{DirBuilderExt, PermissionsExt},
+  path::Path,
+};
+use std::{
+  io,
+  pin::Pin,
+  task::{self, Poll},
+};
+
+use anyhow::Context;
+#[cfg(windows)]
+use tokio::net::windows::named_pipe::{NamedPipeServer, ServerOptions};
+#[cfg(unix)]
+use tokio::net::UnixListener;
+use tokio::{
+  io::{AsyncRead, AsyncWrite, ReadBuf},
+  net::TcpListener,
+};
+#[cfg(windows)]
+use tracing::warn;
+use wm_common::{IpcConfig, IpcTransport};
+
+#[cfg(windows)]
+use crate::security::PrivateSecurityDescriptor;
+use crate::IpcStream;
+
+/// Listener for incoming IPC connections on the configured transport.
+pub enum IpcListener {
+  Tcp(TcpListener),
+  #[cfg(unix)]
+  UnixSocket(UnixListener),
+  #[cfg(windows)]
+  NamedPipe {
+    name: String,
+    /// Pipe instance that the next client will connect to. Recreated on
+    /// the next accept if it failed to be created.
+    next_server: Option<NamedPipeServer>,
+  },
+}
+
+impl IpcListener {
+  /// Starts listening on the transport from the given config.
+  pub async fn bind(ipc_config: &IpcConfig) -> anyhow::Result<Self> {
+    let listener = match ipc_config.transport {
+      IpcTransport::Tcp => {
+        let server_addr = ipc_config.socket_addr();
+
+        Self::Tcp(TcpListener::bind(&server_addr).await.with_context(
+          || format!("Failed to bind IPC server to '{}'.", server_addr),
+        )?)
+      }
+      IpcTransport::LocalSocket => Self::bind_local(ipc_config)?,
+    };
+
+    Ok(listener)
+  }
+
+  /// Starts listening for HTTP requests on a separate TCP port.
+  ///
+  /// Returns `None` if HTTP is disabled or is served on the same listener
+  /// as the websocket server.
+  pub async fn bind_http(
+    ipc_config: &IpcConfig,
+  ) -> anyhow::Result<Option<Self>> {
+    let Some(port) = ipc_config.http.port else {
+      return Ok(None);
+    };
+
+    if !ipc_config.http.enabled {
+      return Ok(None);
+    }
+
+    let server_addr = format!("{}:{}", ipc_config.address, port);
+
+    let listener =
+      TcpListener::bind(&server_addr).await.with_context(|| {
+        format!("Failed to bind HTTP server to '{}'.", server_addr)
+      })?;
+
+    Ok(Some(Self::Tcp(listener)))
+  }
+
+  #[cfg(unix)]
+  fn bind_local(ipc_config: &IpcConfig) -> anyhow::Result<Self> {
+    Self::bind_unix_socket(&ipc_config.local_socket_path()?)
+  }
+
+  /// Starts listening on a Unix domain socket at the given path.
+  ///
+  /// The socket's parent directory is reserved for IPC sockets, and is
+  /// restricted to the current user.
+  #[cfg(unix)]
+  pub fn bind_unix_socket(socket_path: &Path) -> anyhow::Result<Self> {
+    let socket_dir =
+      socket_path.parent().context("Invalid local socket path.")?;
+
+    if let Some(parent_dir) = socket_dir.parent() {
+      fs::create_dir_all(parent_dir)?;
+    }
+
+    // Only allow the current user to connect. The socket is created with
+    // the process umask, so access is restricted via its directory
+    // rather than by changing its permissions after binding.
+    if let Err(err) = fs::DirBuilder::new().mode(0o700).create(socket_dir)
+    {
+      if err.kind() != io::ErrorKind::AlreadyExists {
+        return Err(err.into());
+      }
+    }
+
+    // The directory might be left over from an older version with looser
+    // permissions.
+    fs::set_permissions(socket_dir, fs::Permissions::from_mode(0o700))?;
+
+    // Remove the socket file from a previous run, since binding fails if
+    // it already exists.
+    if socket_path.exists() {
+      fs::remove_file(socket_path)?;
+    }
+
+    let listener = UnixListener::bind(socket_path).with_context(|| {
+      format!("Failed to bind IPC server to '{}'.", socket_path.display())
+    })?;
+
+    Ok(Self::UnixSocket(listener))
+  }
+
+  #[cfg(windows)]
+  fn bind_local(ipc_config: &IpcConfig) -> anyhow::Result<Self> {
+    let name = ipc_config.local_socket_path()?.display().to_string();
+
+    // Fail if another process already owns the pipe, rather than
+    // accepting connections alongside it.
+    let next_server = create_pipe(&name, true).with_context(|| {
+      format!("Failed to bind IPC server to '{}'.", name)
+    })?;
+
+    Ok(Self::NamedPipe {
+      name,
+      next_server: Some(next_server),
+    })
+  }
+
+  /// Human-readable address of the listener (e.g. `127.0.0.1:6123`).
+  pub fn local_addr(&self) -> String {
+    match self {
+      Self::Tcp(listener) => listener
+        .local_addr()
+        .map(|addr| addr.to_string())
+        .unwrap_or_default(),
+      #[cfg(unix)]
+      Self::UnixSocket(listener) => listener
+        .local_addr()
+        .ok()
+        .and_then(|addr| {
+          addr.as_pathname().map(|path| path.display().to_string())
+        })
+        .unwrap_or_default(),
+      #[cfg(windows)]
+      Self::NamedPipe { name, .. } => name.clone(),
+    }
+  }
+
+  /// Waits for the next client to connect.
+  ///
+  /// Returns the stream along with a description of the peer for
+  /// logging.
+  pub async fn accept(
+    &mut self,
+  ) -> io::Result<(Box<dyn IpcStream>, String)> {
+    match self {
+      Self::Tcp(listener) => {
+        let (stream, addr) = listener.accept().await?;
+        Ok((Box::new(stream), addr.to_string()))
+      }
+      #[cfg(unix)]
+      Self::UnixSocket(listener) => {
+        let (stream, _) = listener.accept().await?;
+        Ok((Box::new(stream), "local socket".to_string()))
+      }
+      #[cfg(windows)]
+      Self::NamedPipe { name, next_server } => {
+        let server = match next_server.take() {
+          Some(server) => server,
+          None => create_pipe(name, false)?,
+        };
+
+        server.connect().await?;
+
+        // Create a new pipe instance for the next client before handing
+        // off the connected one. On failure, creating it is retried on
+        // the next accept.
+        *next_server = create_pipe(name, false)
+          .inspect_err(|err| {
+            warn!("Failed to create named pipe instance: {}", err);
+          })
+          .ok();
+
+        Ok((Box::new(server), "named pipe".to_string()))
+      }
+    }
+  }
+}
+
+/// Creates a named pipe instance that only the current user can connect
+/// to.
+///
+/// Pipes otherwise get a default DACL, which grants read access to
+/// everyone.
+#[cfg(windows)]
+fn create_pipe(
+  name: &str,
+  is_first_instance: bool,
+) -> io::Result<NamedPipeServer> {
+  let security_descriptor = PrivateSecurityDescriptor::new()?;
+  let mut security_attributes = security_descriptor.attributes();
+
+  // The attributes point to a valid security descriptor, which
+  // outlives the call.
+  unsafe {
+    ServerOptions::new()
+      .first_pipe_instance(is_first_instance)
+      .create_with_security_attributes_raw(
+        name,
+        &mut security_attributes as *mut _ as *mut c_void,
+      )
+  }
+}
+
+/// Stream that replays already read bytes before reading from the
+/// underlying stream.
+///
+/// Used to hand off a connection after its request head has been
+/// inspected.
+pub struct PrefixedStream {
+  prefix: Vec<u8>,
+  position: usize,
+  inner: Box<dyn IpcStream>,
+}
+
+impl PrefixedStream {
+  pub fn new(prefix: Vec<u8>, inner: Box<dyn IpcStream>) -> Self {
+    Self {
+      prefix,
+      position: 0,
+      inner,
+    }
+  }
+}
+
+impl AsyncRead for PrefixedStream {
+  fn poll_read(
+    self: Pin<&mut Self>,
+    cx: &mut task::Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<io::Result<()>> {
+    let this = self.get_mut();
+
+    if this.position < this.prefix.len() {
+      let remaining = &this.prefix[this.position..];
+      let read_len = remaining.len().min(buf.remaining());
+
+      buf.put_slice(&remaining[..read_len]);
+      this.position += read_len;
+
+      return Poll::Ready(Ok(()));
+    }
+
+    Pin::new(&mut this.inner).poll_read(cx, buf)
+  }
+}
+
+impl AsyncWrite for PrefixedStream {
+  fn poll_write(
+    self: Pin<&mut Self>,
+    cx: &mut task::Context<'_>,
+    buf: &[u8],
+  ) -> Poll<io::Result<usize>> {
+    Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+  }
+
+  fn poll_flush(
+    self: Pin<&mut Self>,
+    cx: &mut task::Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+  }
+
+  fn poll_shutdown(
+    self: Pin<&mut Self>,
+    cx: &mut task::Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+  }
+}
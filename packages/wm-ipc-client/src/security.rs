@@ -0,0 +1,57 @@
+use windows::{
+  core::w,
+  Win32::{
+    Foundation::{LocalFree, HLOCAL},
+    Security::{
+      Authorization::{
+        ConvertStringSecurityDescriptorToSecurityDescriptorW,
+        SDDL_REVISION_1,
+      },
+      PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES,
+    },
+  },
+};
+
+/// Security descriptor with a protected DACL that only grants full access
+/// to the object's owner.
+///
+/// Used for objects that other users shouldn't be able to open, such as
+/// the IPC auth token and named pipe.
+pub struct PrivateSecurityDescriptor(PSECURITY_DESCRIPTOR);
+
+impl PrivateSecurityDescriptor {
+  pub fn new() -> windows::core::Result<Self> {
+    let mut security_descriptor = PSECURITY_DESCRIPTOR::default();
+
+    unsafe {
+      ConvertStringSecurityDescriptorToSecurityDescriptorW(
+        w!("D:P(A;;FA;;;OW)"),
+        SDDL_REVISION_1,
+        &mut security_descriptor,
+        None,
+      )
+    }?;
+
+    Ok(Self(security_descriptor))
+  }
+
+  /// Non-inheritable security attributes that point to this descriptor.
+  ///
+  /// The attributes are only valid for as long as the descriptor is
+  /// alive.
+  pub fn attributes(&self) -> SECURITY_ATTRIBUTES {
+    SECURITY_ATTRIBUTES {
+      nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+      lpSecurityDescriptor: self.0 .0,
+      bInheritHandle: false.into(),
+    }
+  }
+}
+
+impl Drop for PrivateSecurityDescriptor {
+  fn drop(&mut self) {
+    // `LocalFree` returns an error on success in this version of the
+    // `windows` crate, so the result is ignored.
+    let _ = unsafe { LocalFree(HLOCAL(self.0 .0)) };
+  }
+}
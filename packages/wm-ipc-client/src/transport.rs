@@ -0,0 +1,175 @@
+#[cfg(unix)]
+use std::path::Path;
+#[cfg(windows)]
+use std::time::Duration;
+
+#[cfg(unix)]
+use tokio::net::UnixStream;
+use tokio::{
+  io::{AsyncRead, AsyncWrite},
+  net::TcpStream,
+};
+#[cfg(windows)]
+use tokio::{net::windows::named_pipe::ClientOptions, time};
+#[cfg(windows)]
+use windows::Win32::Foundation::ERROR_PIPE_BUSY;
+use wm_common::{IpcConfig, IpcTransport};
+
+/// Byte stream that the websocket protocol is served over.
+pub trait IpcStream: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> IpcStream for T {}
+
+/// Connects to the IPC server on the transport from the given config.
+///
+/// Returns the stream along with the websocket URL to use in the
+/// handshake.
+pub async fn connect(
+  ipc_config: &IpcConfig,
+) -> anyhow::Result<(Box<dyn IpcStream>, String)> {
+  match ipc_config.transport {
+    IpcTransport::Tcp => {
+      let server_addr = ipc_config.socket_addr();
+      let stream = TcpStream::connect(&server_addr).await?;
+      Ok((Box::new(stream), format!("ws://{}", server_addr)))
+    }
+    IpcTransport::LocalSocket => {
+      let stream = connect_local(ipc_config).await?;
+
+      // The host is ignored for local sockets, but is required to form a
+      // valid websocket URL.
+      Ok((stream, "ws://localhost".to_string()))
+    }
+  }
+}
+
+#[cfg(unix)]
+async fn connect_local(
+  ipc_config: &IpcConfig,
+) -> anyhow::Result<Box<dyn IpcStream>> {
+  connect_unix_socket(&ipc_config.local_socket_path()?).await
+}
+
+#[cfg(unix)]
+async fn connect_unix_socket(
+  socket_path: &Path,
+) -> anyhow::Result<Box<dyn IpcStream>> {
+  let stream = UnixStream::connect(socket_path).await?;
+  Ok(Box::new(stream))
+}
+
+#[cfg(windows)]
+async fn connect_local(
+  ipc_config: &IpcConfig,
+) -> anyhow::Result<Box<dyn IpcStream>> {
+  let name = ipc_config.local_socket_path()?.display().to_string();
+
+  // All pipe instances can be busy if several clients connect at once,
+  // in which case the connection is retried shortly after.
+  loop {
+    match ClientOptions::new().open(&name) {
+      Ok(client) => return Ok(Box::new(client)),
+      Err(err) if err.raw_os_error() == Some(ERROR_PIPE_BUSY.0 as i32) => {
+        time::sleep(Duration::from_millis(50)).await;
+      }
+      Err(err) => return Err(err.into()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use futures_util::{SinkExt, StreamExt};
+  use tokio_tungstenite::{
+    accept_async, client_async, tungstenite::Message,
+  };
+
+  use super::*;
+  use crate::IpcListener;
+
+  /// Accepts a single connection on the listener and echoes back the
+  /// first websocket message.
+  async fn echo_once(mut listener: IpcListener) {
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut websocket = accept_async(stream).await.unwrap();
+    let message = websocket.next().await.unwrap().unwrap();
+    websocket.send(message).await.unwrap();
+  }
+
+  /// Sends a message over the given stream and returns the echoed
+  /// response.
+  async fn round_trip(
+    stream: Box<dyn IpcStream>,
+    server_url: String,
+  ) -> Message {
+    let (mut websocket, _) =
+      client_async(server_url, stream).await.unwrap();
+
+    websocket
+      .send(Message::Text("query focused".into()))
+      .await
+      .unwrap();
+
+    websocket.next().await.unwrap().unwrap()
+  }
+
+  #[tokio::test]
+  async fn tcp_round_trip() {
+    let listener = IpcListener::bind(&IpcConfig {
+      port: 0,
+      transport: IpcTransport::Tcp,
+      ..IpcConfig::default()
+    })
+    .await
+    .unwrap();
+
+    // Connect to the port that was assigned by the OS.
+    let server_addr = listener.local_addr();
+    let (_, port) = server_addr.rsplit_once(':').unwrap();
+
+    let ipc_config = IpcConfig {
+      port: port.parse().unwrap(),
+      transport: IpcTransport::Tcp,
+      ..IpcConfig::default()
+    };
+
+    let server = tokio::spawn(echo_once(listener));
+    let (stream, server_url) = connect(&ipc_config).await.unwrap();
+
+    assert_eq!(
+      round_trip(stream, server_url).await,
+      Message::Text("query focused".into())
+    );
+
+    server.await.unwrap();
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn unix_socket_round_trip() {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    let temp_dir = std::env::temp_dir()
+      .join(format!("glazewm-test-{}", uuid::Uuid::new_v4()));
+    let socket_path = temp_dir.join("ipc/6123.sock");
+
+    let listener = IpcListener::bind_unix_socket(&socket_path).unwrap();
+
+    // Only the dedicated socket directory is restricted.
+    let mode =
+      |path: &Path| fs::metadata(path).unwrap().permissions().mode();
+    assert_eq!(mode(socket_path.parent().unwrap()) & 0o777, 0o700);
+    assert_ne!(mode(&temp_dir) & 0o777, 0o700);
+
+    let server = tokio::spawn(echo_once(listener));
+    let stream = connect_unix_socket(&socket_path).await.unwrap();
+
+    assert_eq!(
+      round_trip(stream, "ws://localhost".into()).await,
+      Message::Text("query focused".into())
+    );
+
+    server.await.unwrap();
+    fs::remove_dir_all(temp_dir).unwrap();
+  }
+}
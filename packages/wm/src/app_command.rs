@@ -1,10 +1,13 @@
-use std::{iter, path::PathBuf};
+use std::{path::PathBuf, time::Duration};
 
-use anyhow::{bail, Context};
-use clap::{error::KindFormatter, Args, Parser, ValueEnum};
-use serde::{Deserialize, Deserializer, Serialize};
+use anyhow::bail;
+use clap::{Args, Parser, ValueEnum};
 use tracing::{warn, Level};
 use uuid::Uuid;
+use wm_common::{ChainedCommand, ContainerDto};
+pub use wm_common::{
+  InvokeCommand, IpcArgs, SubscribableEvent, TitleBarVisibility,
+};
 
 use crate::{
   common::{
@@ -12,17 +15,20 @@ use crate::{
       cycle_focus, disable_binding_mode, enable_binding_mode,
       reload_config, shell_exec,
     },
-    Direction, LengthValue, RectDelta, TilingDirection,
+    LengthValue, RectDelta,
   },
   containers::{
     commands::{
       focus_in_direction, set_tiling_direction, toggle_tiling_direction,
     },
     traits::CommonGetters,
-    Container,
+    Container, WindowContainer,
   },
   monitors::commands::focus_monitor,
-  user_config::{FloatingStateConfig, FullscreenStateConfig, UserConfig},
+  user_config::{
+    FloatingStateConfig, FullscreenStateConfig, MatchType, UserConfig,
+    WindowMatchConfig,
+  },
   windows::{
     commands::{
       ignore_window, move_window_in_direction, move_window_to_workspace,
@@ -53,6 +59,9 @@ pub enum AppCommand {
 
     #[clap(flatten)]
     verbosity: Verbosity,
+
+    #[clap(flatten)]
+    ipc: IpcArgs,
   },
 
   /// Retrieves and outputs a specific part of the window manager's state.
@@ -62,6 +71,17 @@ pub enum AppCommand {
   Query {
     #[clap(subcommand)]
     command: QueryCommand,
+
+    /// Format to output the response in.
+    #[clap(long, global = true, value_enum, default_value_t = OutputFormat::Json)]
+    format: OutputFormat,
+
+    /// Max time to wait for a response (e.g. `500ms` or `10s`).
+    #[clap(long, global = true, value_parser = parse_duration)]
+    timeout: Option<Duration>,
+
+    #[clap(flatten)]
+    ipc: IpcArgs,
   },
 
   /// Invokes a window manager command.
@@ -72,8 +92,15 @@ pub enum AppCommand {
     #[clap(long = "id")]
     subject_container_id: Option<Uuid>,
 
+    /// Max time to wait for a response (e.g. `500ms` or `10s`).
+    #[clap(long, value_parser = parse_duration)]
+    timeout: Option<Duration>,
+
     #[clap(subcommand)]
     command: InvokeCommand,
+
+    #[clap(flatten)]
+    ipc: IpcArgs,
   },
 
   /// Subscribes to one or more WM events (e.g. `window_close`), and
@@ -84,6 +111,16 @@ pub enum AppCommand {
     /// WM event(s) to subscribe to.
     #[clap(short = 'e', long, value_enum, num_args = 1..)]
     events: Vec<SubscribableEvent>,
+
+    #[clap(flatten)]
+    filter: EventFilterArgs,
+
+    /// Format to output the incoming events in.
+    #[clap(long, value_enum, default_value_t = OutputFormat::Json)]
+    format: OutputFormat,
+
+    #[clap(flatten)]
+    ipc: IpcArgs,
   },
 
   /// Unsubscribes from a prior event subscription.
@@ -93,6 +130,72 @@ pub enum AppCommand {
     /// Subscription ID to unsubscribe from.
     #[clap(long = "id")]
     subscription_id: Uuid,
+
+    #[clap(flatten)]
+    ipc: IpcArgs,
+  },
+
+  /// Waits until a WM event matching the given criteria occurs, then
+  /// outputs the event's container and exits.
+  ///
+  /// For `window-managed` with window flags, an already managed window
+  /// that matches is output right away.
+  ///
+  /// Requires an already running instance of the window manager.
+  WaitFor {
+    /// WM event to wait for.
+    #[clap(value_enum)]
+    event: WaitForEvent,
+
+    #[clap(flatten)]
+    filter: EventFilterArgs,
+
+    /// Max time to wait for a matching event (e.g. `500ms` or `10s`).
+    /// Waits indefinitely if not set.
+    #[clap(long, value_parser = parse_duration)]
+    timeout: Option<Duration>,
+
+    /// Format to output the matching container in.
+    #[clap(long, value_enum, default_value_t = OutputFormat::Json)]
+    format: OutputFormat,
+
+    /// WM command to run with the matching container as its subject
+    /// (e.g. `-- move --workspace 2`).
+    #[clap(last = true)]
+    command: Vec<String>,
+
+    #[clap(flatten)]
+    ipc: IpcArgs,
+  },
+
+  /// Outputs the JSON schema of the user config or of IPC messages.
+  ///
+  /// The config schema can be used by editors to validate and
+  /// autocomplete `config.yaml`.
+  Schema {
+    #[clap(value_enum)]
+    target: SchemaTarget,
+  },
+
+  /// Outputs a script that enables shell completions.
+  ///
+  /// Workspace and binding mode names are completed from the config
+  /// file, and container IDs from a running instance.
+  Completions {
+    #[clap(value_enum)]
+    shell: CompletionShell,
+  },
+
+  /// Checks a config file for errors without starting the window
+  /// manager.
+  ///
+  /// Exits with code 5 if any errors are found.
+  ValidateConfig {
+    /// Path to the config file.
+    ///
+    /// Defaults to the same path that the window manager reads from.
+    #[clap(value_hint = clap::ValueHint::FilePath)]
+    config_path: Option<PathBuf>,
   },
 }
 
@@ -108,12 +211,95 @@ impl AppCommand {
           verbose: false,
           quiet: false,
         },
+        ipc: IpcArgs::default(),
       },
-      false => AppCommand::parse_from(args),
+      false => {
+        // Only the first of any chained commands (e.g. `command focus
+        // --workspace 1 && close`) is parsed here. The IPC server parses
+        // the rest.
+        let first_command_len = args
+          .iter()
+          .position(|arg| arg == ";" || arg == "&&")
+          .unwrap_or(args.len());
+
+        AppCommand::parse_from(&args[..first_command_len])
+      }
+    }
+  }
+}
+
+/// Parses a duration from a number with a unit (e.g. `500ms`, `10s` or
+/// `1m`). Numbers without a unit are treated as seconds.
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+  let unit_index = value
+    .find(|char: char| !char.is_ascii_digit() && char != '.')
+    .unwrap_or(value.len());
+
+  let (amount, unit) = value.split_at(unit_index);
+
+  let amount = amount
+    .parse::<f64>()
+    .map_err(|_| format!("Invalid duration '{}'.", value))?;
+
+  let seconds = match unit {
+    "ms" => amount / 1000.,
+    "" | "s" => amount,
+    "m" => amount * 60.,
+    _ => {
+      return Err(format!(
+        "Invalid duration unit '{}'. Use `ms`, `s` or `m`.",
+        unit
+      ))
     }
+  };
+
+  // Negative, infinite or NaN amounts are rejected here, since
+  // `Duration::from_secs_f64` would panic on them.
+  match Duration::try_from_secs_f64(seconds) {
+    Ok(duration) if !duration.is_zero() => Ok(duration),
+    _ => Err(format!(
+      "Invalid duration '{}'. Must be greater than zero.",
+      value
+    )),
   }
 }
 
+/// Type of JSON schema to output via `AppCommand::Schema`.
+#[derive(Clone, Debug, PartialEq, ValueEnum)]
+#[clap(rename_all = "snake_case")]
+pub enum SchemaTarget {
+  /// Schema of the user config file.
+  Config,
+  /// Schema of messages sent to and from the IPC server.
+  Ipc,
+}
+
+/// Shell to output a completion script for via
+/// `AppCommand::Completions`.
+#[derive(Clone, Debug, PartialEq, ValueEnum)]
+#[clap(rename_all = "snake_case")]
+pub enum CompletionShell {
+  Bash,
+  Fish,
+  Powershell,
+  Zsh,
+}
+
+/// Output format of CLI responses and events.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+#[clap(rename_all = "kebab-case")]
+pub enum OutputFormat {
+  /// Single-line JSON.
+  Json,
+  /// Indented JSON.
+  JsonPretty,
+  /// YAML document.
+  Yaml,
+  /// Compact table of containers. Falls back to YAML for responses
+  /// without containers.
+  Table,
+}
+
 /// Verbosity flags to be used with `#[command(flatten)]`.
 #[derive(Args, Clone, Debug)]
 #[clap(about = None, long_about = None)]
@@ -142,6 +328,9 @@ impl Verbosity {
 pub enum QueryCommand {
   /// Outputs metadata about the application (e.g. version number).
   AppMetadata,
+  /// Outputs the IPC protocol version, along with the supported queries,
+  /// events and features.
+  Capabilities,
   /// Outputs the active binding modes.
   BindingModes,
   /// Outputs the focused container (either a window or an empty
@@ -150,117 +339,363 @@ pub enum QueryCommand {
   /// Outputs the tiling direction of the focused container.
   TilingDirection,
   /// Outputs all monitors.
-  Monitors,
+  Monitors {
+    #[clap(flatten)]
+    filter: QueryFilterArgs,
+  },
   /// Outputs all windows.
-  Windows,
+  Windows {
+    #[clap(flatten)]
+    filter: QueryFilterArgs,
+  },
   /// Outputs all active workspaces.
-  Workspaces,
+  Workspaces {
+    #[clap(flatten)]
+    filter: QueryFilterArgs,
+  },
+  /// Outputs the full container tree, starting from the root container.
+  Tree {
+    /// Maximum depth of descendants to include (e.g. `1` to only include
+    /// monitors).
+    #[clap(long)]
+    depth: Option<usize>,
+  },
+  /// Outputs a single container (and its descendants) by its ID.
+  Container {
+    /// ID of the container to output.
+    #[clap(long)]
+    id: Uuid,
+
+    /// Maximum depth of descendants to include.
+    #[clap(long)]
+    depth: Option<usize>,
+  },
 }
 
-#[derive(Clone, Debug, PartialEq, ValueEnum)]
-#[clap(rename_all = "snake_case")]
-pub enum SubscribableEvent {
-  All,
-  ApplicationExiting,
-  BindingModesChanged,
+/// Filter and projection flags for container queries, to be used with
+/// `#[command(flatten)]`.
+///
+/// Window filters (e.g. `--process`) match workspaces and monitors that
+/// contain at least one matching window.
+#[derive(Args, Clone, Debug, Default)]
+#[clap(about = None, long_about = None)]
+pub struct QueryFilterArgs {
+  #[clap(flatten)]
+  pub window: WindowMatchArgs,
+
+  /// Only include containers within the workspace with the given name.
+  #[clap(long)]
+  pub workspace: Option<String>,
+
+  /// Only include containers on the monitor with the given index.
+  #[clap(long)]
+  pub monitor: Option<usize>,
+
+  /// Only include windows in the given state.
+  #[clap(long, value_enum)]
+  pub state: Option<WindowStateFilter>,
+
+  /// Comma-separated list of keys to include for each container (e.g.
+  /// `id,title,processName`). All keys are included by default.
+  #[clap(long, value_delimiter = ',')]
+  pub fields: Vec<String>,
+}
+
+impl QueryFilterArgs {
+  /// Validates the filters (e.g. that `--title-regex` is a valid regex).
+  pub fn validate(&self) -> anyhow::Result<()> {
+    self.window.validate()
+  }
+
+  /// Whether the given container matches all of the filters.
+  pub fn is_match(
+    &self,
+    container: &impl CommonGetters,
+  ) -> anyhow::Result<bool> {
+    if let Some(monitor_index) = self.monitor {
+      let is_monitor_match = container
+        .monitor()
+        .map(|monitor| monitor.index() == monitor_index)
+        .unwrap_or(false);
+
+      if !is_monitor_match {
+        return Ok(false);
+      }
+    }
+
+    if let Some(workspace_name) = &self.workspace {
+      // Monitors are matched by the workspaces they contain.
+      let is_workspace_match = container
+        .self_and_ancestors()
+        .chain(container.descendants())
+        .filter_map(|container| container.as_workspace().cloned())
+        .any(|workspace| &workspace.config().name == workspace_name);
+
+      if !is_workspace_match {
+        return Ok(false);
+      }
+    }
+
+    if !self.has_window_filters() {
+      return Ok(true);
+    }
+
+    for descendant in container.self_and_descendants() {
+      if let Ok(window) = descendant.as_window_container() {
+        if self.is_window_match(&window)? {
+          return Ok(true);
+        }
+      }
+    }
+
+    Ok(false)
+  }
+
+  fn has_window_filters(&self) -> bool {
+    self.window.is_set() || self.state.is_some()
+  }
+
+  /// Whether the window matches the window filters.
+  fn is_window_match(
+    &self,
+    window: &WindowContainer,
+  ) -> anyhow::Result<bool> {
+    if let Some(state) = &self.state {
+      if !state.is_match(&window.state()) {
+        return Ok(false);
+      }
+    }
+
+    Ok(self.window.to_match_config().is_match(
+      &window.native().process_name()?,
+      &window.native().class_name()?,
+      &window.native().title()?,
+    ))
+  }
+}
+
+/// Window match flags to be used with `#[command(flatten)]`.
+#[derive(Args, Clone, Debug, Default)]
+#[clap(about = None, long_about = None)]
+pub struct WindowMatchArgs {
+  /// Only match windows with the given process name.
+  #[clap(long)]
+  pub process: Option<String>,
+
+  /// Only match windows with the given class name.
+  #[clap(long)]
+  pub class: Option<String>,
+
+  /// Only match windows with a title matching the given regex.
+  #[clap(long)]
+  pub title_regex: Option<String>,
+}
+
+impl WindowMatchArgs {
+  /// Validates that `--title-regex` is a valid regex.
+  pub fn validate(&self) -> anyhow::Result<()> {
+    if let Some(title_regex) = &self.title_regex {
+      regex::Regex::new(title_regex)
+        .with_context(|| format!("Invalid regex '{}'.", title_regex))?;
+    }
+
+    Ok(())
+  }
+
+  /// Whether any of the flags are set.
+  pub fn is_set(&self) -> bool {
+    self.process.is_some()
+      || self.class.is_some()
+      || self.title_regex.is_some()
+  }
+
+  /// Converts the flags to a `WindowMatchConfig`, so that matching
+  /// behaves the same as for window rules.
+  pub fn to_match_config(&self) -> WindowMatchConfig {
+    WindowMatchConfig {
+      window_process: self
+        .process
+        .clone()
+        .map(|equals| MatchType::Equals { equals }),
+      window_class: self
+        .class
+        .clone()
+        .map(|equals| MatchType::Equals { equals }),
+      window_title: self
+        .title_regex
+        .clone()
+        .map(|regex| MatchType::Regex { regex }),
+    }
+  }
+}
+
+/// Event to wait for via `AppCommand::WaitFor`.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+#[clap(rename_all = "kebab-case")]
+pub enum WaitForEvent {
+  #[value(alias = "focus_changed")]
   FocusChanged,
-  FocusedContainerMoved,
-  MonitorAdded,
-  MonitorUpdated,
-  MonitorRemoved,
-  TilingDirectionChanged,
-  UserConfigChanged,
+  #[value(alias = "window_managed")]
   WindowManaged,
-  WindowUnmanaged,
+  #[value(alias = "workspace_activated")]
   WorkspaceActivated,
-  WorkspaceDeactivated,
-  WorkspaceUpdated,
 }
 
-#[derive(Clone, Debug, Parser, PartialEq, Serialize)]
-pub enum InvokeCommand {
-  AdjustBorders(InvokeAdjustBordersCommand),
-  Close,
-  Focus(InvokeFocusCommand),
-  Ignore,
-  Move(InvokeMoveCommand),
-  MoveWorkspace {
-    #[clap(long)]
-    direction: Direction,
-  },
-  Resize(InvokeResizeCommand),
-  SetFloating {
-    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
-    shown_on_top: Option<bool>,
+impl WaitForEvent {
+  /// Event to subscribe to for this event.
+  pub fn subscribable_event(&self) -> SubscribableEvent {
+    match self {
+      WaitForEvent::FocusChanged => SubscribableEvent::FocusChanged,
+      WaitForEvent::WindowManaged => SubscribableEvent::WindowManaged,
+      WaitForEvent::WorkspaceActivated => {
+        SubscribableEvent::WorkspaceActivated
+      }
+    }
+  }
 
-    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
-    centered: Option<bool>,
-  },
-  SetFullscreen {
-    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
-    shown_on_top: Option<bool>,
+  /// Whether the container of an event matches the window flags.
+  ///
+  /// Window events only match if the window itself matches, whereas
+  /// workspace events match if any window within the workspace does.
+  pub fn is_match(
+    &self,
+    container: &ContainerDto,
+    window_args: &WindowMatchArgs,
+  ) -> bool {
+    if !window_args.is_set() {
+      return true;
+    }
 
-    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
-    maximized: Option<bool>,
-  },
-  SetMinimized,
-  SetTiling,
-  SetTitleBarVisibility {
-    #[clap(required = true, value_enum)]
-    visibility: TitleBarVisibility,
-  },
-  ShellExec {
-    #[clap(long, action)]
-    hide_window: bool,
+    let match_config = window_args.to_match_config();
 
-    #[clap(required = true, trailing_var_arg = true)]
-    command: Vec<String>,
-  },
-  // Reuse `InvokeResizeCommand` struct.
-  Size(InvokeResizeCommand),
-  ToggleFloating {
-    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
-    shown_on_top: Option<bool>,
-
-    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
-    centered: Option<bool>,
-  },
-  ToggleFullscreen {
-    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
-    shown_on_top: Option<bool>,
+    let windows = match (self, container) {
+      (WaitForEvent::WorkspaceActivated, _) => {
+        container.self_and_descendant_windows()
+      }
+      (_, ContainerDto::Window(window)) => vec![window],
+      _ => vec![],
+    };
+
+    windows.iter().any(|window| {
+      match_config.is_match(
+        &window.process_name,
+        &window.class_name,
+        &window.title,
+      )
+    })
+  }
+}
 
-    #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
-    maximized: Option<bool>,
-  },
-  ToggleMinimized,
-  ToggleTiling,
-  ToggleTilingDirection,
-  SetTilingDirection {
-    #[clap(required = true)]
-    tiling_direction: TilingDirection,
-  },
-  WmCycleFocus {
-    #[clap(long, default_value_t = false)]
-    omit_fullscreen: bool,
+/// Filter flags for event subscriptions, to be used with
+/// `#[command(flatten)]`.
+///
+/// Events that aren't tied to a container (e.g. `application_exiting`)
+/// are only forwarded if no filters are set.
+#[derive(Args, Clone, Debug, Default)]
+#[clap(about = None, long_about = None)]
+pub struct EventFilterArgs {
+  // Matches events whose container is, or contains, a matching window.
+  #[clap(flatten)]
+  pub window: WindowMatchArgs,
 
-    #[clap(long, default_value_t = true)]
-    omit_minimized: bool,
-  },
-  WmDisableBindingMode {
-    #[clap(long)]
-    name: String,
-  },
-  WmEnableBindingMode {
-    #[clap(long)]
-    name: String,
-  },
-  WmExit,
-  WmRedraw,
-  WmReloadConfig,
+  /// Only forward events for containers within the workspace with the
+  /// given name.
+  #[clap(long)]
+  pub workspace: Option<String>,
+
+  /// Only forward events for containers on the monitor with the given
+  /// index.
+  #[clap(long)]
+  pub monitor: Option<usize>,
+
+  /// Only forward events for the container with the given ID.
+  #[clap(long)]
+  pub container_id: Option<Uuid>,
+}
+
+impl EventFilterArgs {
+  /// Converts the filter back to CLI flags (e.g. `--workspace 1`), for
+  /// forwarding to the IPC server.
+  pub fn to_args(&self) -> Vec<String> {
+    let flags = [
+      ("--process", self.window.process.clone()),
+      ("--class", self.window.class.clone()),
+      ("--title-regex", self.window.title_regex.clone()),
+      ("--workspace", self.workspace.clone()),
+      ("--monitor", self.monitor.map(|index| index.to_string())),
+      ("--container-id", self.container_id.map(|id| id.to_string())),
+    ];
+
+    flags
+      .into_iter()
+      .filter_map(|(flag, value)| {
+        value.map(|value| [flag.to_string(), value])
+      })
+      .flatten()
+      .collect()
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, ValueEnum)]
+#[clap(rename_all = "snake_case")]
+pub enum WindowStateFilter {
+  Floating,
+  Fullscreen,
+  Minimized,
+  Tiling,
+}
+
+impl WindowStateFilter {
+  /// Whether the given window state is of this type.
+  pub fn is_match(&self, state: &WindowState) -> bool {
+    matches!(
+      (self, state),
+      (WindowStateFilter::Floating, WindowState::Floating(_))
+        | (WindowStateFilter::Fullscreen, WindowState::Fullscreen(_))
+        | (WindowStateFilter::Minimized, WindowState::Minimized)
+        | (WindowStateFilter::Tiling, WindowState::Tiling)
+    )
+  }
+}
+
+/// Extension trait for running `InvokeCommand`s against the WM state.
+///
+/// `InvokeCommand` is defined in `wm_common`, so that IPC clients can
+/// construct commands without depending on the WM itself.
+pub trait InvokeCommandExt {
+  fn run(
+    &self,
+    subject_container: Container,
+    state: &mut WmState,
+    config: &mut UserConfig,
+  ) -> anyhow::Result<()>;
+
+  /// Runs the given commands via `run_batch`, and returns the first
+  /// error of the commands that failed.
+  fn run_multiple(
+    commands: Vec<ChainedCommand>,
+    subject_container: Container,
+    state: &mut WmState,
+    config: &mut UserConfig,
+  ) -> anyhow::Result<Uuid>;
+
+  /// Runs the given commands in order. A command chained with `&&` is
+  /// skipped if the previous command failed or was skipped, whereas a
+  /// command chained with `;` is always run. Stops without an error once
+  /// the subject container no longer exists (e.g. after `close`).
+  ///
+  /// Returns the result of each command that was run or skipped, along
+  /// with the ID of the final subject container.
+  fn run_batch(
+    commands: Vec<ChainedCommand>,
+    subject_container: Container,
+    state: &mut WmState,
+    config: &mut UserConfig,
+  ) -> (Vec<anyhow::Result<()>>, Uuid);
 }
 
-impl InvokeCommand {
-  pub fn run(
+impl InvokeCommandExt for InvokeCommand {
+  fn run(
     &self,
     subject_container: Container,
     state: &mut WmState,
@@ -532,7 +967,20 @@ impl InvokeCommand {
       InvokeCommand::ShellExec {
         hide_window,
         command,
-      } => shell_exec(&command.join(" "), *hide_window),
+      } => {
+        // Arguments were unquoted when parsing the command, so quote the
+        // ones containing whitespace (e.g. paths with spaces).
+        let command = command
+          .iter()
+          .map(|arg| match arg.contains(char::is_whitespace) {
+            true => format!("\"{}\"", arg),
+            false => arg.clone(),
+          })
+          .collect::<Vec<_>>()
+          .join(" ");
+
+        shell_exec(&command, *hide_window)
+      }
       InvokeCommand::Size(args) => {
         match subject_container.as_window_container() {
           Ok(window) => set_window_size(
@@ -665,16 +1113,44 @@ impl InvokeCommand {
     }
   }
 
-  pub fn run_multiple(
-    commands: Vec<InvokeCommand>,
+  fn run_multiple(
+    commands: Vec<ChainedCommand>,
     subject_container: Container,
     state: &mut WmState,
     config: &mut UserConfig,
   ) -> anyhow::Result<Uuid> {
+    let (results, subject_container_id) =
+      Self::run_batch(commands, subject_container, state, config);
+
+    for result in results {
+      result?;
+    }
+
+    Ok(subject_container_id)
+  }
+
+  fn run_batch(
+    commands: Vec<ChainedCommand>,
+    subject_container: Container,
+    state: &mut WmState,
+    config: &mut UserConfig,
+  ) -> (Vec<anyhow::Result<()>>, Uuid) {
+    let mut results = Vec::new();
     let mut current_subject_container = subject_container;
+    let mut is_prev_success = true;
 
-    for command in commands {
-      command.run(current_subject_container.clone(), state, config)?;
+    for chained in commands {
+      let result = match chained.should_run(is_prev_success) {
+        true => chained.command.run(
+          current_subject_container.clone(),
+          state,
+          config,
+        ),
+        false => Err(anyhow::anyhow!("Skipped due to a prior error.")),
+      };
+
+      is_prev_success = result.is_ok();
+      results.push(result);
 
       // Update the subject container in case the container type changes.
       // For example, when going from a tiling to a floating window.
@@ -690,114 +1166,6 @@ impl InvokeCommand {
         }
     }
 
-    Ok(current_subject_container.id())
-  }
-}
-
-impl<'de> Deserialize<'de> for InvokeCommand {
-  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-  where
-    D: Deserializer<'de>,
-  {
-    // Clap expects an array of string slices where the first argument is
-    // the binary name/path. When deserializing commands from the user
-    // config, we therefore have to prepend an additional empty argument.
-    let unparsed = String::deserialize(deserializer)?;
-    let unparsed_split = iter::once("").chain(unparsed.split_whitespace());
-
-    InvokeCommand::try_parse_from(unparsed_split).map_err(|err| {
-      // Format the error message and remove the "error: " prefix.
-      let err_msg = err.apply::<KindFormatter>().to_string();
-      serde::de::Error::custom(err_msg.trim_start_matches("error: "))
-    })
+    (results, current_subject_container.id())
   }
 }
-
-#[derive(Clone, Debug, PartialEq, Serialize, ValueEnum)]
-#[clap(rename_all = "snake_case")]
-#[serde(rename_all = "snake_case")]
-pub enum TitleBarVisibility {
-  Shown,
-  Hidden,
-}
-
-#[derive(Args, Clone, Debug, PartialEq, Serialize)]
-#[group(required = true, multiple = true)]
-pub struct InvokeAdjustBordersCommand {
-  #[clap(long, allow_hyphen_values = true)]
-  top: Option<LengthValue>,
-
-  #[clap(long, allow_hyphen_values = true)]
-  right: Option<LengthValue>,
-
-  #[clap(long, allow_hyphen_values = true)]
-  bottom: Option<LengthValue>,
-
-  #[clap(long, allow_hyphen_values = true)]
-  left: Option<LengthValue>,
-}
-
-#[derive(Args, Clone, Debug, PartialEq, Serialize)]
-#[group(required = true, multiple = false)]
-pub struct InvokeFocusCommand {
-  #[clap(long)]
-  direction: Option<Direction>,
-
-  #[clap(long)]
-  workspace: Option<String>,
-
-  #[clap(long)]
-  monitor: Option<usize>,
-
-  #[clap(long)]
-  next_active_workspace: bool,
-
-  #[clap(long)]
-  prev_active_workspace: bool,
-
-  #[clap(long)]
-  next_workspace: bool,
-
-  #[clap(long)]
-  prev_workspace: bool,
-
-  #[clap(long)]
-  recent_workspace: bool,
-}
-
-#[derive(Args, Clone, Debug, PartialEq, Serialize)]
-#[group(required = true, multiple = false)]
-pub struct InvokeMoveCommand {
-  /// Direction to move the window.
-  #[clap(long)]
-  direction: Option<Direction>,
-
-  /// Name of workspace to move the window.
-  #[clap(long)]
-  workspace: Option<String>,
-
-  #[clap(long)]
-  next_active_workspace: bool,
-
-  #[clap(long)]
-  prev_active_workspace: bool,
-
-  #[clap(long)]
-  next_workspace: bool,
-
-  #[clap(long)]
-  prev_workspace: bool,
-
-  #[clap(long)]
-  recent_workspace: bool,
-}
-
-#[derive(Args, Clone, Debug, PartialEq, Serialize)]
-#[group(required = true, multiple = true)]
-pub struct InvokeResizeCommand {
-  #[clap(long, allow_hyphen_values = true)]
-  width: Option<LengthValue>,
-
-  #[clap(long, allow_hyphen_values = true)]
-  height: Option<LengthValue>,
-}
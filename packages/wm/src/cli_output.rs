@@ -0,0 +1,539 @@
+use std::{collections::HashMap, process};
+
+use anyhow::Context;
+use serde::Serialize;
+use tokio::time::error::Elapsed;
+use uuid::Uuid;
+use wm_common::{
+  ClientResponseData, ClientResponseMessage, ContainerDto,
+  EventSubscriptionMessage, WindowState, WmEvent, WorkspaceDto,
+};
+
+use crate::app_command::OutputFormat;
+
+/// Maximum number of characters to show in the title column.
+const MAX_TITLE_WIDTH: usize = 48;
+
+/// Column widths of event tables. Events are output as they arrive, so
+/// the widths can't be derived from the rows.
+const EVENT_COLUMN_WIDTHS: [usize; 6] = [24, 36, 32, 16, 18, 16];
+
+/// Exit code of a CLI command, so that scripts can branch on the type of
+/// failure.
+///
+/// Arguments that clap rejects also exit with code 2.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CliExitCode {
+  /// The command or query was rejected by the WM.
+  CommandFailed = 1,
+  /// Arguments were invalid in a way that clap doesn't check (e.g. an
+  /// invalid regex).
+  InvalidArgs = 2,
+  /// No running instance of the WM could be connected to.
+  NotRunning = 3,
+  /// The WM didn't respond in time.
+  Timeout = 4,
+  /// The config file passed to `validate-config` has errors or couldn't
+  /// be read.
+  InvalidConfig = 5,
+}
+
+/// Error from a CLI command, along with the code to exit with.
+#[derive(Debug)]
+pub struct CliError {
+  pub exit_code: CliExitCode,
+  pub err: anyhow::Error,
+}
+
+impl CliError {
+  pub fn new(exit_code: CliExitCode, err: anyhow::Error) -> Self {
+    Self { exit_code, err }
+  }
+
+  /// Error for a failed connection to the IPC server.
+  pub fn from_connect_error(err: anyhow::Error) -> Self {
+    match Self::is_timeout(&err) {
+      true => Self::new(CliExitCode::Timeout, err),
+      false => Self::new(CliExitCode::NotRunning, err),
+    }
+  }
+
+  /// Error for a failed request to the IPC server.
+  pub fn from_request_error(err: anyhow::Error) -> Self {
+    match Self::is_timeout(&err) {
+      true => Self::new(CliExitCode::Timeout, err),
+      false => Self::new(CliExitCode::CommandFailed, err),
+    }
+  }
+
+  fn is_timeout(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<Elapsed>())
+  }
+
+  /// Outputs the error to stderr and exits the process.
+  pub fn exit(self) -> ! {
+    eprintln!("error: {:#}", self.err);
+    process::exit(self.exit_code as i32)
+  }
+}
+
+impl From<anyhow::Error> for CliError {
+  fn from(err: anyhow::Error) -> Self {
+    Self::new(CliExitCode::CommandFailed, err)
+  }
+}
+
+/// Formats IPC responses and events for output by the CLI.
+///
+/// Keeps track of which workspace each container belongs to, so that
+/// table rows can show the workspace of a window.
+pub struct CliOutput {
+  format: OutputFormat,
+  workspace_names: HashMap<Uuid, String>,
+}
+
+/// Single row of a container table.
+struct TableRow {
+  id: String,
+  title: String,
+  process: String,
+  state: String,
+  workspace: String,
+}
+
+impl CliOutput {
+  pub fn new(format: OutputFormat) -> Self {
+    Self {
+      format,
+      workspace_names: HashMap::new(),
+    }
+  }
+
+  /// Records the workspace of the given workspace's descendants.
+  pub fn track_workspace(&mut self, workspace: &WorkspaceDto) {
+    self
+      .workspace_names
+      .insert(workspace.id, workspace.name.clone());
+
+    for child in &workspace.children {
+      self.track_descendants(child, &workspace.name);
+    }
+  }
+
+  /// Records the workspace of the given container and its descendants.
+  ///
+  /// Containers that aren't within a workspace payload (e.g. a newly
+  /// managed window) get the workspace of their parent, if it's known.
+  fn track(&mut self, container: &ContainerDto) {
+    if let ContainerDto::Workspace(workspace) = container {
+      self.track_workspace(workspace);
+      return;
+    }
+
+    let workspace_name = self
+      .workspace_names
+      .get(&container.id())
+      .or_else(|| {
+        container
+          .parent_id()
+          .and_then(|parent_id| self.workspace_names.get(&parent_id))
+      })
+      .cloned();
+
+    match workspace_name {
+      Some(workspace_name) => {
+        self.track_descendants(container, &workspace_name)
+      }
+      None => {
+        for child in container.children() {
+          self.track(child);
+        }
+      }
+    }
+  }
+
+  fn track_descendants(
+    &mut self,
+    container: &ContainerDto,
+    workspace_name: &str,
+  ) {
+    self
+      .workspace_names
+      .insert(container.id(), workspace_name.to_string());
+
+    for child in container.children() {
+      self.track_descendants(child, workspace_name);
+    }
+  }
+
+  /// Formats a response to a query or command.
+  pub fn format_response(
+    &mut self,
+    response: &ClientResponseMessage,
+  ) -> anyhow::Result<String> {
+    if self.format != OutputFormat::Table {
+      return self.serialize(response);
+    }
+
+    let containers = match &response.data {
+      Some(ClientResponseData::Monitors(data)) => data.monitors.clone(),
+      Some(ClientResponseData::Windows(data)) => data.windows.clone(),
+      Some(ClientResponseData::Workspaces(data)) => {
+        data.workspaces.clone()
+      }
+      Some(ClientResponseData::Focused(data)) => {
+        vec![data.focused.clone()]
+      }
+      Some(ClientResponseData::Container(data)) => {
+        vec![data.container.clone()]
+      }
+      Some(ClientResponseData::Tree(data)) => vec![data.root.clone()],
+      // Remaining responses don't contain containers, so fall back to
+      // YAML which is readable enough as-is.
+      _ => return Self::to_yaml(&response.data),
+    };
+
+    for container in &containers {
+      self.track(container);
+    }
+
+    // Flatten the tree, since nested containers can't be shown in a
+    // single table.
+    let rows: Vec<TableRow> = match &response.data {
+      Some(ClientResponseData::Tree(_)) => containers
+        .iter()
+        .flat_map(|container| self.descendant_rows(container))
+        .collect(),
+      _ => containers
+        .iter()
+        .filter_map(|container| self.container_row(container))
+        .collect(),
+    };
+
+    Ok(Self::render_table(&rows))
+  }
+
+  /// Formats a single container (e.g. the container of an awaited
+  /// event).
+  pub fn format_container(
+    &mut self,
+    container: &ContainerDto,
+  ) -> anyhow::Result<String> {
+    if self.format != OutputFormat::Table {
+      return self.serialize(container);
+    }
+
+    self.track(container);
+
+    let rows = self
+      .container_row(container)
+      .into_iter()
+      .collect::<Vec<_>>();
+    Ok(Self::render_table(&rows))
+  }
+
+  /// Header line of event tables. Returns `None` for non-table formats.
+  pub fn event_header(&self) -> Option<String> {
+    (self.format == OutputFormat::Table).then(|| {
+      Self::render_event_line([
+        "EVENT",
+        "ID",
+        "TITLE",
+        "PROCESS",
+        "STATE",
+        "WORKSPACE",
+      ])
+    })
+  }
+
+  /// Formats an event from an event subscription.
+  pub fn format_event(
+    &mut self,
+    event_message: &EventSubscriptionMessage,
+  ) -> anyhow::Result<String> {
+    if self.format != OutputFormat::Table {
+      return self.serialize(event_message);
+    }
+
+    if let Some(lagged_count) = event_message.lagged_count {
+      return Ok(Self::render_event_line([
+        "events_missed",
+        "-",
+        &format!("{} events missed", lagged_count),
+        "-",
+        "-",
+        "-",
+      ]));
+    }
+
+    let event = event_message
+      .data
+      .as_ref()
+      .context("No data in event message.")?;
+
+    if let Some(container) = event.container() {
+      self.track(container);
+    }
+
+    let event_type = serde_json::to_value(event)?
+      .get("eventType")
+      .and_then(|event_type| event_type.as_str())
+      .context("Invalid event type.")?
+      .to_string();
+
+    let row = match event {
+      WmEvent::BindingModesChanged { new_binding_modes } => TableRow {
+        title: new_binding_modes
+          .iter()
+          .map(|binding_mode| binding_mode.name.clone())
+          .collect::<Vec<_>>()
+          .join(", "),
+        ..TableRow::empty()
+      },
+      WmEvent::MonitorRemoved {
+        removed_id,
+        removed_device_name,
+      } => TableRow {
+        id: removed_id.to_string(),
+        title: removed_device_name.clone(),
+        ..TableRow::empty()
+      },
+      WmEvent::UserConfigChanged { config_path, .. } => TableRow {
+        title: config_path.clone(),
+        ..TableRow::empty()
+      },
+      WmEvent::UserConfigReloadFailed { error, .. } => TableRow {
+        title: error.lines().next().unwrap_or_default().to_string(),
+        ..TableRow::empty()
+      },
+      WmEvent::WindowUnmanaged {
+        unmanaged_id,
+        unmanaged_handle,
+      } => TableRow {
+        id: unmanaged_id.to_string(),
+        title: format!("handle {}", unmanaged_handle),
+        workspace: self.workspace_name(unmanaged_id, None),
+        ..TableRow::empty()
+      },
+      WmEvent::WorkspaceDeactivated {
+        deactivated_id,
+        deactivated_name,
+      } => TableRow {
+        id: deactivated_id.to_string(),
+        title: deactivated_name.clone(),
+        workspace: deactivated_name.clone(),
+        ..TableRow::empty()
+      },
+      _ => event
+        .container()
+        .and_then(|container| self.container_row(container))
+        .unwrap_or_else(TableRow::empty),
+    };
+
+    Ok(Self::render_event_line([
+      &event_type,
+      &row.id,
+      &row.title,
+      &row.process,
+      &row.state,
+      &row.workspace,
+    ]))
+  }
+
+  fn serialize<T: Serialize>(&self, value: &T) -> anyhow::Result<String> {
+    match self.format {
+      OutputFormat::JsonPretty => Ok(serde_json::to_string_pretty(value)?),
+      OutputFormat::Yaml => Self::to_yaml(value),
+      OutputFormat::Json | OutputFormat::Table => {
+        Ok(serde_json::to_string(value)?)
+      }
+    }
+  }
+
+  fn to_yaml<T: Serialize>(value: &T) -> anyhow::Result<String> {
+    // Trim the trailing newline, since the output is printed with
+    // `println!`.
+    Ok(serde_yaml::to_string(value)?.trim_end().to_string())
+  }
+
+  /// Rows of the given container and its descendants. Root and split
+  /// containers are omitted.
+  fn descendant_rows(&self, container: &ContainerDto) -> Vec<TableRow> {
+    self
+      .container_row(container)
+      .into_iter()
+      .chain(
+        container
+          .children()
+          .iter()
+          .flat_map(|child| self.descendant_rows(child)),
+      )
+      .collect()
+  }
+
+  /// Table row for a monitor, workspace or window.
+  fn container_row(&self, container: &ContainerDto) -> Option<TableRow> {
+    let row = match container {
+      ContainerDto::Monitor(monitor) => TableRow {
+        id: monitor.id.to_string(),
+        title: monitor.device_name.clone(),
+        process: "-".to_string(),
+        state: Self::focus_state(None, monitor.has_focus),
+        workspace: monitor
+          .children
+          .iter()
+          .find_map(|child| match child {
+            ContainerDto::Workspace(workspace)
+              if workspace.is_displayed =>
+            {
+              Some(workspace.name.clone())
+            }
+            _ => None,
+          })
+          .unwrap_or_else(|| "-".to_string()),
+      },
+      ContainerDto::Workspace(workspace) => TableRow {
+        id: workspace.id.to_string(),
+        title: workspace
+          .display_name
+          .clone()
+          .unwrap_or_else(|| workspace.name.clone()),
+        process: "-".to_string(),
+        state: Self::focus_state(
+          Some(match workspace.is_displayed {
+            true => "displayed",
+            false => "hidden",
+          }),
+          workspace.has_focus,
+        ),
+        workspace: workspace.name.clone(),
+      },
+      ContainerDto::Window(window) => TableRow {
+        id: window.id.to_string(),
+        title: window.title.clone(),
+        process: window.process_name.clone(),
+        state: Self::focus_state(
+          Some(match window.state {
+            WindowState::Floating(_) => "floating",
+            WindowState::Fullscreen(_) => "fullscreen",
+            WindowState::Minimized => "minimized",
+            WindowState::Tiling => "tiling",
+          }),
+          window.has_focus,
+        ),
+        workspace: self.workspace_name(&window.id, window.parent_id),
+      },
+      ContainerDto::Root(_) | ContainerDto::Split(_) => return None,
+    };
+
+    Some(row)
+  }
+
+  /// Name of the workspace that the container (or its parent) belongs
+  /// to, or `-` if it hasn't been tracked.
+  fn workspace_name(&self, id: &Uuid, parent_id: Option<Uuid>) -> String {
+    self
+      .workspace_names
+      .get(id)
+      .or_else(|| {
+        parent_id
+          .and_then(|parent_id| self.workspace_names.get(&parent_id))
+      })
+      .cloned()
+      .unwrap_or_else(|| "-".to_string())
+  }
+
+  /// Joins the state of a container with whether it has focus (e.g.
+  /// `tiling, focused`).
+  fn focus_state(state: Option<&str>, has_focus: bool) -> String {
+    match (state, has_focus) {
+      (Some(state), true) => format!("{}, focused", state),
+      (Some(state), false) => state.to_string(),
+      (None, true) => "focused".to_string(),
+      (None, false) => "-".to_string(),
+    }
+  }
+
+  /// Renders rows as a table with columns sized to fit their contents.
+  fn render_table(rows: &[TableRow]) -> String {
+    let header = ["ID", "TITLE", "PROCESS", "STATE", "WORKSPACE"];
+
+    let cells = rows
+      .iter()
+      .map(|row| {
+        [
+          row.id.clone(),
+          truncate(&row.title, MAX_TITLE_WIDTH),
+          row.process.clone(),
+          row.state.clone(),
+          row.workspace.clone(),
+        ]
+      })
+      .collect::<Vec<_>>();
+
+    let widths = header.map(str::len);
+    let widths = cells.iter().fold(widths, |mut widths, row| {
+      for (width, cell) in widths.iter_mut().zip(row) {
+        *width = (*width).max(cell.chars().count());
+      }
+
+      widths
+    });
+
+    std::iter::once(header.map(str::to_string))
+      .chain(cells)
+      .map(|row| render_line(&row, &widths))
+      .collect::<Vec<_>>()
+      .join("\n")
+  }
+
+  fn render_event_line(cells: [&str; 6]) -> String {
+    let cells = cells
+      .iter()
+      .zip(EVENT_COLUMN_WIDTHS)
+      .map(|(cell, width)| truncate(cell, width))
+      .collect::<Vec<_>>();
+
+    render_line(&cells, &EVENT_COLUMN_WIDTHS)
+  }
+}
+
+impl TableRow {
+  fn empty() -> Self {
+    Self {
+      id: "-".to_string(),
+      title: "-".to_string(),
+      process: "-".to_string(),
+      state: "-".to_string(),
+      workspace: "-".to_string(),
+    }
+  }
+}
+
+/// Pads each cell to its column width, separated by two spaces. The last
+/// cell isn't padded to avoid trailing whitespace.
+fn render_line(cells: &[String], widths: &[usize]) -> String {
+  cells
+    .iter()
+    .zip(widths)
+    .enumerate()
+    .map(|(index, (cell, width))| match index == cells.len() - 1 {
+      true => cell.clone(),
+      false => format!("{:<width$}", cell, width = width),
+    })
+    .collect::<Vec<_>>()
+    .join("  ")
+}
+
+/// Truncates a string to the given number of characters, ending with an
+/// ellipsis if truncated.
+fn truncate(value: &str, max_width: usize) -> String {
+  match value.chars().count() > max_width {
+    true => value
+      .chars()
+      .take(max_width - 1)
+      .chain(std::iter::once('…'))
+      .collect(),
+    false => value.to_string(),
+  }
+}
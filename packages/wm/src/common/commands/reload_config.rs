@@ -2,8 +2,11 @@ use anyhow::Context;
 use tracing::{info, warn};
 
 use crate::{
-  app_command::InvokeCommand,
-  containers::traits::{CommonGetters, TilingSizeGetters},
+  app_command::{InvokeCommand, InvokeCommandExt},
+  containers::{
+    commands::set_tiling_direction,
+    traits::{CommonGetters, TilingSizeGetters},
+  },
   user_config::{ParsedConfig, UserConfig, WindowRuleEvent},
   windows::{commands::run_window_rules, traits::WindowGetters},
   wm_event::WmEvent,
@@ -15,13 +18,21 @@ pub fn reload_config(
   state: &mut WmState,
   config: &mut UserConfig,
 ) -> anyhow::Result<()> {
-  info!("Config reloaded.");
-
   // Keep reference to old config for comparison.
   let old_config = config.value.clone();
 
-  // Re-evaluate user config file and set its values in state.
-  config.reload()?;
+  // Re-evaluate user config file. The config is only replaced if it's
+  // valid, so an invalid config leaves the previous one in place.
+  if let Err(err) = config.reload() {
+    state.emit_event(WmEvent::UserConfigReloadFailed {
+      config_path: config_path_str(config)?,
+      error: format!("{:#}", err),
+    });
+
+    return Err(err.context("Failed to reload config."));
+  }
+
+  info!("Config reloaded.");
 
   // Re-run window rules on all active windows.
   for window in state.windows() {
@@ -47,13 +58,9 @@ pub fn reload_config(
 
   // Emit the updated config.
   state.emit_event(WmEvent::UserConfigChanged {
-    config_path: config
-      .path
-      .to_str()
-      .context("Invalid config path.")?
-      .to_string(),
+    config_path: config_path_str(config)?,
     config_string: config.value_str.clone(),
-    parsed_config: config.value.clone(),
+    parsed_config: Box::new(config.value.clone()),
   });
 
   // Run config reload commands.
@@ -67,6 +74,16 @@ pub fn reload_config(
   Ok(())
 }
 
+fn config_path_str(config: &UserConfig) -> anyhow::Result<String> {
+  Ok(
+    config
+      .path
+      .to_str()
+      .context("Invalid config path.")?
+      .to_string(),
+  )
+}
+
 /// Update configs of active workspaces.
 fn update_workspace_configs(
   state: &mut WmState,
@@ -87,7 +104,11 @@ fn update_workspace_configs(
         // workspace has been removed. So, we reassign the first suitable
         // workspace config to the workspace.
         config
-          .workspace_config_for_monitor(&monitor, &workspaces)
+          .workspace_config_for_monitor(
+            &monitor,
+            &state.monitors(),
+            &workspaces,
+          )
           .or_else(|| config.next_inactive_workspace_config(&workspaces))
       });
 
@@ -98,9 +119,29 @@ fn update_workspace_configs(
         );
       }
       Some(workspace_config) => {
-        if *workspace_config != workspace.config() {
+        let old_workspace_config = workspace.config();
+
+        if *workspace_config != old_workspace_config {
           workspace.set_config(workspace_config.clone());
 
+          // Apply the tiling direction if it was changed in the config.
+          // The direction is otherwise left as is, since it might have
+          // been changed via commands.
+          if let Some(tiling_direction) =
+            &workspace_config.tiling_direction
+          {
+            if old_workspace_config.tiling_direction.as_ref()
+              != Some(tiling_direction)
+            {
+              set_tiling_direction(
+                workspace.clone().into(),
+                state,
+                config,
+                tiling_direction.clone(),
+              )?;
+            }
+          }
+
           sort_workspaces(monitor, config)?;
 
           state.emit_event(WmEvent::WorkspaceUpdated {
@@ -126,7 +167,8 @@ fn update_container_gaps(state: &mut WmState, config: &UserConfig) {
   }
 
   for workspace in state.workspaces() {
-    workspace.set_gaps_config(config.value.gaps.clone());
+    workspace
+      .set_gaps_config(config.workspace_gaps_config(&workspace.config()));
   }
 }
 
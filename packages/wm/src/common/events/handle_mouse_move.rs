@@ -14,7 +14,7 @@ pub fn handle_mouse_move(
 ) -> anyhow::Result<()> {
   // Ignore event if left/right-click is down. Otherwise, this causes focus
   // to jitter when a window is being resized by its drag handles.
-  if event.is_mouse_down || !config.value.general.focus_follows_cursor {
+  if event.is_mouse_down || !config.has_focus_follows_cursor() {
     return Ok(());
   }
 
@@ -22,7 +22,14 @@ pub fn handle_mouse_move(
     .and_then(|window| Platform::root_ancestor(&window))
     .map(|root| state.window_from_native(&root))?;
 
-  // Set focus to whichever window is currently under the cursor.
+  // Set focus to whichever window is currently under the cursor, unless
+  // focus-follows-cursor is disabled for its workspace.
+  let window_under_cursor = window_under_cursor.filter(|window| {
+    window.workspace().is_some_and(|workspace| {
+      config.focus_follows_cursor(&workspace.config())
+    })
+  });
+
   if let Some(window) = window_under_cursor {
     let focused_container =
       state.focused_container().context("No focused container.")?;
@@ -61,7 +61,7 @@ pub fn handle_window_location_changed(
 
           let target_state = window
             .prev_state()
-            .unwrap_or(WindowState::default_from_config(config));
+            .unwrap_or(WindowState::default_from_config(&config.value));
 
           update_window_state(
             window.clone(),
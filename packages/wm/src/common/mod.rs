@@ -1,25 +1,13 @@
-mod color;
 pub mod commands;
-mod direction;
-mod display_state;
 pub mod events;
-mod length_value;
 mod memo;
 pub mod platform;
-mod point;
-mod rect;
-mod rect_delta;
-mod tiling_direction;
 mod try_warn;
 mod vec_deque_ext;
 
-pub use color::*;
-pub use direction::*;
-pub use display_state::*;
-pub use length_value::*;
 pub use memo::*;
-pub use point::*;
-pub use rect::*;
-pub use rect_delta::*;
-pub use tiling_direction::*;
 pub use vec_deque_ext::*;
+pub use wm_common::{
+  Color, Direction, DisplayState, LengthUnit, LengthValue, Point, Rect,
+  RectDelta, TilingDirection,
+};
@@ -48,7 +48,7 @@ impl EventListener {
     let event_window = EventWindow::new(
       event_tx,
       &config.value.keybindings,
-      config.value.general.focus_follows_cursor,
+      config.has_focus_follows_cursor(),
     )?;
 
     Ok(Self {
@@ -72,6 +72,6 @@ impl EventListener {
 
     self
       .event_window
-      .update(keybindings, config.value.general.focus_follows_cursor);
+      .update(keybindings, config.has_focus_follows_cursor());
   }
 }
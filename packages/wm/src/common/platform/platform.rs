@@ -1,14 +1,19 @@
 use std::{
-  os::windows::io::AsRawHandle,
+  fs::{self, File},
+  io::Write,
+  os::windows::io::{AsRawHandle, FromRawHandle},
   path::{Path, PathBuf},
   thread::JoinHandle,
 };
 
 use anyhow::{bail, Context};
 use windows::{
-  core::{w, PCWSTR},
+  core::{w, HSTRING, PCWSTR},
   Win32::{
-    Foundation::{HANDLE, HWND, LPARAM, POINT, WPARAM},
+    Foundation::{GENERIC_WRITE, HANDLE, HWND, LPARAM, POINT, WPARAM},
+    Storage::FileSystem::{
+      CreateFileW, CREATE_NEW, FILE_ATTRIBUTE_NORMAL, FILE_SHARE_NONE,
+    },
     System::{
       Environment::ExpandEnvironmentStringsW, Threading::GetThreadId,
     },
@@ -32,6 +37,7 @@ use windows::{
     },
   },
 };
+use wm_ipc_client::PrivateSecurityDescriptor;
 
 use super::{
   native_monitor, native_window, EventListener, NativeMonitor,
@@ -124,8 +130,10 @@ impl Platform {
   }
 
   /// Creates a new `SingleInstance`.
-  pub fn new_single_instance() -> anyhow::Result<SingleInstance> {
-    SingleInstance::new()
+  pub fn new_single_instance(
+    ipc_port: u16,
+  ) -> anyhow::Result<SingleInstance> {
+    SingleInstance::new(ipc_port)
   }
 
   // Gets the root window of the specified window.
@@ -459,6 +467,47 @@ impl Platform {
     Ok(())
   }
 
+  /// Writes a file that only the current user can access. Replaces the
+  /// file if it already exists.
+  ///
+  /// The file gets an explicit ACL rather than inheriting one from its
+  /// parent directory, which might grant access to other users.
+  pub fn write_private_file(
+    path: &Path,
+    contents: &str,
+  ) -> anyhow::Result<()> {
+    let security_descriptor = PrivateSecurityDescriptor::new()
+      .context("Failed to create security descriptor.")?;
+    let security_attributes = security_descriptor.attributes();
+
+    // Remove any existing file, since its ACL would otherwise be kept.
+    if path.exists() {
+      fs::remove_file(path)?;
+    }
+
+    let handle = unsafe {
+      CreateFileW(
+        &HSTRING::from(path),
+        GENERIC_WRITE.0,
+        FILE_SHARE_NONE,
+        Some(&security_attributes),
+        CREATE_NEW,
+        FILE_ATTRIBUTE_NORMAL,
+        HANDLE::default(),
+      )
+    };
+
+    let handle = handle.with_context(|| {
+      format!("Unable to create file {}.", path.display())
+    })?;
+
+    // The file takes ownership of the handle and closes it on drop.
+    let mut file = unsafe { File::from_raw_handle(handle.0 as _) };
+    file.write_all(contents.as_bytes())?;
+
+    Ok(())
+  }
+
   pub fn show_error_dialog(title: &str, message: &str) {
     let title_wide = to_wide(title);
     let message_wide = to_wide(message);
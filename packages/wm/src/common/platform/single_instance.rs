@@ -1,6 +1,6 @@
 use anyhow::{bail, Context, Result};
 use windows::{
-  core::{w, PCWSTR},
+  core::PCWSTR,
   Win32::{
     Foundation::{
       CloseHandle, GetLastError, ERROR_ALREADY_EXISTS,
@@ -12,21 +12,24 @@ use windows::{
     },
   },
 };
+use wm_common::DEFAULT_IPC_PORT;
 
 pub struct SingleInstance {
   handle: HANDLE,
 }
 
 /// Arbitrary GUID used to identify the application.
-const APP_GUID: PCWSTR =
-  w!("Global\\325d0ed7-7f60-4925-8d1b-aa287b26b218");
+const APP_GUID: &str = "Global\\325d0ed7-7f60-4925-8d1b-aa287b26b218";
 
 impl SingleInstance {
   /// Creates a new system-wide mutex to ensure that only one instance of
-  /// the application is running.
-  pub fn new() -> Result<Self> {
-    let handle = unsafe { CreateMutexW(None, true, APP_GUID) }
-      .context("Failed to create single instance mutex.")?;
+  /// the application is running per IPC port.
+  pub fn new(ipc_port: u16) -> Result<Self> {
+    let mutex_name = Self::mutex_name(ipc_port);
+
+    let handle =
+      unsafe { CreateMutexW(None, true, PCWSTR(mutex_name.as_ptr())) }
+        .context("Failed to create single instance mutex.")?;
 
     if let Err(err) = unsafe { GetLastError() } {
       if err == ERROR_ALREADY_EXISTS.into() {
@@ -37,10 +40,17 @@ impl SingleInstance {
     Ok(Self { handle })
   }
 
-  /// Gets whether there is an active instance of the application.
-  pub fn is_running() -> bool {
+  /// Gets whether there is an active instance of the application on the
+  /// given IPC port.
+  pub fn is_running(ipc_port: u16) -> bool {
+    let mutex_name = Self::mutex_name(ipc_port);
+
     let res = unsafe {
-      OpenMutexW(SYNCHRONIZATION_ACCESS_RIGHTS::default(), false, APP_GUID)
+      OpenMutexW(
+        SYNCHRONIZATION_ACCESS_RIGHTS::default(),
+        false,
+        PCWSTR(mutex_name.as_ptr()),
+      )
     };
 
     // Check whether the mutex exists. If it doesn't, then this is the
@@ -50,6 +60,19 @@ impl SingleInstance {
       Err(err) => err == ERROR_FILE_NOT_FOUND.into(),
     }
   }
+
+  /// Gets the null-terminated mutex name for the given IPC port.
+  ///
+  /// Instances on the default port use the bare app GUID, so that the
+  /// mutex stays compatible with older versions.
+  fn mutex_name(ipc_port: u16) -> Vec<u16> {
+    let name = match ipc_port == DEFAULT_IPC_PORT {
+      true => APP_GUID.to_string(),
+      false => format!("{}-{}", APP_GUID, ipc_port),
+    };
+
+    name.encode_utf16().chain(Some(0)).collect()
+  }
 }
 
 impl Drop for SingleInstance {
@@ -0,0 +1,220 @@
+use std::{env, future::Future, io, time::Duration};
+
+use anyhow::Context;
+use clap::{Command, CommandFactory};
+use clap_complete::{
+  engine::{ArgValueCandidates, CompletionCandidate},
+  env::{Bash, EnvCompleter, Fish, Powershell, Zsh},
+  CompleteEnv,
+};
+use tokio::runtime::Handle;
+use wm_common::{ContainerDto, IpcArgs};
+use wm_ipc_client::{IpcClient, IpcClientOptions};
+
+use crate::{
+  app_command::{AppCommand, CompletionShell},
+  user_config::UserConfig,
+};
+
+/// Environment variable that the shell sets when requesting completions
+/// from the binary.
+const COMPLETE_ENV: &str = "GLAZEWM_COMPLETE";
+
+/// Max time to wait for a running instance when completing values.
+/// Completions should stay responsive when no instance is running.
+const QUERY_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Outputs completions and exits the process if the shell is requesting
+/// completions. Otherwise, this is a no-op.
+///
+/// Needs to be called before the command line arguments are parsed.
+pub fn complete_if_requested() {
+  CompleteEnv::with_factory(|| with_candidates(AppCommand::command()))
+    .var(COMPLETE_ENV)
+    .complete();
+}
+
+/// Outputs the script that registers completions with the given shell.
+///
+/// The script calls back into the binary on each completion, so that
+/// workspace names and container IDs are up-to-date.
+pub fn print_completions(shell: CompletionShell) -> anyhow::Result<()> {
+  let completer: &dyn EnvCompleter = match shell {
+    CompletionShell::Bash => &Bash,
+    CompletionShell::Fish => &Fish,
+    CompletionShell::Powershell => &Powershell,
+    CompletionShell::Zsh => &Zsh,
+  };
+
+  let exe_path = env::current_exe()?;
+  let exe_path = exe_path
+    .to_str()
+    .context("Path to executable isn't valid UTF-8.")?;
+
+  completer.write_registration(
+    COMPLETE_ENV,
+    "glazewm",
+    "glazewm",
+    exe_path,
+    &mut io::stdout(),
+  )?;
+
+  Ok(())
+}
+
+/// Adds dynamic value candidates to the arguments of the command and its
+/// subcommands.
+fn with_candidates(mut command: Command) -> Command {
+  let command_name = command.get_name().to_string();
+
+  let arg_ids = command
+    .get_arguments()
+    .map(|arg| arg.get_id().to_string())
+    .collect::<Vec<_>>();
+
+  for arg_id in arg_ids {
+    let candidates = match (command_name.as_str(), arg_id.as_str()) {
+      (_, "workspace") => ArgValueCandidates::new(workspace_candidates),
+      ("wm-enable-binding-mode" | "wm-disable-binding-mode", "name") => {
+        ArgValueCandidates::new(binding_mode_candidates)
+      }
+      (_, "subject_container_id") | ("container", "id") => {
+        ArgValueCandidates::new(container_candidates)
+      }
+      _ => continue,
+    };
+
+    command = command.mut_arg(arg_id, |arg| arg.add(candidates));
+  }
+
+  let subcommand_names = command
+    .get_subcommands()
+    .map(|subcommand| subcommand.get_name().to_string())
+    .collect::<Vec<_>>();
+
+  for subcommand_name in subcommand_names {
+    command = command.mut_subcommand(subcommand_name, with_candidates);
+  }
+
+  command
+}
+
+/// Workspace names from the config file, along with any active
+/// workspaces of a running instance that aren't in the config.
+fn workspace_candidates() -> Vec<CompletionCandidate> {
+  let mut candidates = UserConfig::read_default()
+    .map(|config| config.workspaces)
+    .unwrap_or_default()
+    .into_iter()
+    .map(|workspace| {
+      CompletionCandidate::new(&workspace.name)
+        .help(workspace.display_name.map(Into::into))
+    })
+    .collect::<Vec<_>>();
+
+  let active_workspaces =
+    query_running_instance(|mut client| async move {
+      client.query_workspaces().await
+    })
+    .unwrap_or_default();
+
+  for workspace in active_workspaces {
+    let is_listed = candidates
+      .iter()
+      .any(|candidate| candidate.get_value() == workspace.name.as_str());
+
+    if !is_listed {
+      candidates.push(
+        CompletionCandidate::new(&workspace.name)
+          .help(workspace.display_name.map(Into::into)),
+      );
+    }
+  }
+
+  candidates
+}
+
+/// Binding mode names from the config file.
+fn binding_mode_candidates() -> Vec<CompletionCandidate> {
+  UserConfig::read_default()
+    .map(|config| config.binding_modes)
+    .unwrap_or_default()
+    .into_iter()
+    .map(|binding_mode| {
+      CompletionCandidate::new(&binding_mode.name)
+        .help(binding_mode.display_name.map(Into::into))
+    })
+    .collect()
+}
+
+/// IDs of the windows and workspaces of a running instance.
+fn container_candidates() -> Vec<CompletionCandidate> {
+  query_running_instance(
+    |mut client| async move { client.query_tree().await },
+  )
+  .map(|root| {
+    let mut candidates = Vec::new();
+    add_container_candidates(&root, &mut candidates);
+    candidates
+  })
+  .unwrap_or_default()
+}
+
+fn add_container_candidates(
+  container: &ContainerDto,
+  candidates: &mut Vec<CompletionCandidate>,
+) {
+  let help = match container {
+    ContainerDto::Workspace(workspace) => {
+      Some(format!("Workspace {}", workspace.name))
+    }
+    ContainerDto::Window(window) => {
+      Some(format!("{}: {}", window.process_name, window.title))
+    }
+    _ => None,
+  };
+
+  if let Some(help) = help {
+    candidates.push(
+      CompletionCandidate::new(container.id().to_string())
+        .help(Some(help.into())),
+    );
+  }
+
+  for child in container.children() {
+    add_container_candidates(child, candidates);
+  }
+}
+
+/// Runs a query against a running instance. Returns `None` if there is
+/// no running instance or the query fails.
+fn query_running_instance<T, F, Fut>(query: F) -> Option<T>
+where
+  F: FnOnce(IpcClient) -> Fut,
+  Fut: Future<Output = anyhow::Result<T>>,
+{
+  block_on(async {
+    let ipc_config =
+      UserConfig::client_ipc_config(&IpcArgs::default()).ok()?;
+
+    let client = IpcClient::connect_with_options(
+      &ipc_config,
+      IpcClientOptions {
+        connect_timeout: QUERY_TIMEOUT,
+        request_timeout: QUERY_TIMEOUT,
+        reconnect_attempts: 0,
+        ..Default::default()
+      },
+    )
+    .await
+    .ok()?;
+
+    query(client).await.ok()
+  })
+}
+
+/// Blocks on a future from within the async runtime. Value candidates
+/// are fetched synchronously by `clap_complete`.
+fn block_on<F: Future>(future: F) -> F::Output {
+  tokio::task::block_in_place(|| Handle::current().block_on(future))
+}
@@ -0,0 +1,627 @@
+use std::{
+  collections::{HashMap, HashSet},
+  fmt, fs,
+  path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use glob::Pattern;
+use serde_yaml::{Mapping, Value};
+use wm_common::ParsedConfig;
+
+use crate::{
+  config_validation::ConfigDiagnostic,
+  config_variables::{expand_config, VARIABLES_KEY},
+};
+
+/// Key in a config file that lists other config files to merge in.
+const INCLUDE_KEY: &str = "include";
+
+/// Config file that makes up part of the user config. Either the main
+/// config file or a file that it includes.
+#[derive(Clone, Debug)]
+pub struct ConfigFile {
+  pub path: PathBuf,
+
+  /// Unparsed contents of the file.
+  pub source: String,
+
+  /// Parsed YAML of the file, before merging.
+  pub value: Value,
+
+  /// Number of includes between the main config file and this file.
+  /// `0` for the main config file.
+  pub depth: usize,
+
+  /// Directories that the globs under `include` match files in (e.g.
+  /// `machines` for `machines/*.yaml`).
+  pub glob_dirs: Vec<PathBuf>,
+}
+
+/// Error in one of the config files, along with the file that it came
+/// from.
+#[derive(Clone, Debug)]
+pub struct ConfigFileError {
+  pub path: PathBuf,
+  pub source: String,
+  pub diagnostic: ConfigDiagnostic,
+}
+
+impl ConfigFileError {
+  /// Formats the error with a snippet of the offending line, if known.
+  pub fn render(&self) -> String {
+    self.diagnostic.render(&self.path, &self.source)
+  }
+}
+
+impl fmt::Display for ConfigFileError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {}", self.path.display(), self.diagnostic)
+  }
+}
+
+impl std::error::Error for ConfigFileError {}
+
+/// Reads the config file at the given path, along with any files that it
+/// includes via `include`.
+///
+/// Included paths are relative to the file that includes them and can
+/// contain globs (e.g. `machines/*.yaml`). The main config file is
+/// returned first, and each file is followed by the files that it
+/// includes. Files that were already read are skipped, so that includes
+/// can't loop.
+pub fn read_config_files(
+  config_path: &Path,
+) -> anyhow::Result<Vec<ConfigFile>> {
+  let mut files = Vec::new();
+  let mut visited = HashSet::new();
+
+  read_config_file(config_path, 0, &mut files, &mut visited)?;
+
+  Ok(files)
+}
+
+fn read_config_file(
+  path: &Path,
+  depth: usize,
+  files: &mut Vec<ConfigFile>,
+  visited: &mut HashSet<PathBuf>,
+) -> anyhow::Result<()> {
+  let canonical_path =
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+  if !visited.insert(canonical_path) {
+    return Ok(());
+  }
+
+  let source = fs::read_to_string(path).with_context(|| {
+    format!("Unable to read config file {}.", path.display())
+  })?;
+
+  let value = match serde_yaml::from_str::<Value>(&source) {
+    Ok(Value::Null) => Value::Mapping(Mapping::new()),
+    Ok(value) => value,
+    Err(err) => {
+      let diagnostic = ConfigDiagnostic::from_yaml_error(&err, &source);
+      return Err(file_error(path, &source, diagnostic).into());
+    }
+  };
+
+  let (include_paths, glob_dirs) = include_paths(path, &source, &value)?;
+
+  files.push(ConfigFile {
+    path: path.to_path_buf(),
+    source,
+    value,
+    depth,
+    glob_dirs,
+  });
+
+  for include_path in include_paths {
+    read_config_file(&include_path, depth + 1, files, visited)?;
+  }
+
+  Ok(())
+}
+
+/// Resolves the paths listed under `include` in the given file, along
+/// with the directories that any globs match files in.
+fn include_paths(
+  path: &Path,
+  source: &str,
+  value: &Value,
+) -> anyhow::Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+  let includes = match value.get(INCLUDE_KEY) {
+    None | Some(Value::Null) => return Ok((Vec::new(), Vec::new())),
+    Some(Value::Sequence(includes)) => includes
+      .iter()
+      .map(|include| include.as_str())
+      .collect::<Option<Vec<_>>>()
+      .ok_or_else(|| invalid_include(path, source))?,
+    Some(_) => return Err(invalid_include(path, source).into()),
+  };
+
+  let config_dir = path.parent().unwrap_or(Path::new(""));
+  let mut paths = Vec::new();
+  let mut glob_dirs = Vec::new();
+
+  for include in includes {
+    let is_glob = include.contains(['*', '?', '[']);
+
+    if !is_glob {
+      let include_path = config_dir.join(include);
+
+      if !include_path.is_file() {
+        return Err(
+          file_error(
+            path,
+            source,
+            ConfigDiagnostic {
+              message: format!(
+                "Included config file {} doesn't exist.",
+                include_path.display()
+              ),
+              location: None,
+            },
+          )
+          .into(),
+        );
+      }
+
+      paths.push(include_path);
+      continue;
+    }
+
+    // The directory is the part of the pattern before the first glob
+    // (e.g. `machines` for `machines/*.yaml`).
+    let glob_dir = Path::new(include)
+      .components()
+      .take_while(|component| {
+        !component
+          .as_os_str()
+          .to_string_lossy()
+          .contains(['*', '?', '['])
+      })
+      .collect::<PathBuf>();
+
+    glob_dirs.push(config_dir.join(glob_dir));
+
+    // Escape the directory, so that only the included path is treated
+    // as a pattern.
+    let config_dir = config_dir
+      .to_str()
+      .context("Config path isn't valid UTF-8.")?;
+
+    let pattern = Path::new(&Pattern::escape(config_dir)).join(include);
+    let pattern = pattern
+      .to_str()
+      .context("Included path isn't valid UTF-8.")?;
+
+    let matches = glob::glob(pattern).map_err(|err| {
+      file_error(
+        path,
+        source,
+        ConfigDiagnostic {
+          message: format!(
+            "Invalid include pattern '{}': {}",
+            include, err
+          ),
+          location: None,
+        },
+      )
+    })?;
+
+    // Matches are sorted alphabetically, which makes the merge order
+    // predictable.
+    for include_path in matches {
+      let include_path = include_path?;
+
+      if include_path.is_file() {
+        paths.push(include_path);
+      }
+    }
+  }
+
+  Ok((paths, glob_dirs))
+}
+
+/// Merges config files into a single config.
+///
+/// The files that a file includes are merged first (in the order that
+/// they're listed), followed by the file itself. Values in the main
+/// config file therefore take precedence over included files, and
+/// included files take precedence over the files that they include.
+///
+/// Mappings are merged key by key, lists (e.g. `keybindings` or
+/// `window_rules`) are appended to, and all other values are overridden
+/// by later files. Variables and keybinding templates are expanded once
+/// all files are merged, so that they can be used across files.
+pub fn merge_config_files(
+  files: &[ConfigFile],
+) -> anyhow::Result<ParsedConfig> {
+  let Some((main_file, included_files)) = files.split_first() else {
+    anyhow::bail!("No config files to merge.");
+  };
+
+  let mut merged = Value::Mapping(Mapping::new());
+  let mut origins = HashMap::new();
+
+  for index in merge_order(files) {
+    merge_value(
+      &mut merged,
+      files[index].value.clone(),
+      "",
+      index,
+      &mut origins,
+    );
+  }
+
+  let is_expanded = expand_config(&mut merged).map_err(|err| {
+    let file = &files[origin_of(VARIABLES_KEY, &origins)];
+
+    file_error(
+      &file.path,
+      &file.source,
+      ConfigDiagnostic {
+        message: err.to_string(),
+        location: None,
+      },
+    )
+  })?;
+
+  // Deserialize the main file from its source when it's used as is, so
+  // that errors point to their line.
+  if included_files.is_empty() && !is_expanded {
+    return serde_yaml::from_str(&main_file.source).map_err(|err| {
+      let diagnostic =
+        ConfigDiagnostic::from_yaml_error(&err, &main_file.source);
+      file_error(&main_file.path, &main_file.source, diagnostic).into()
+    });
+  }
+
+  // Deserializing from a string, rather than from the value, gives
+  // errors the path to the invalid value. The path is used to find the
+  // file that the value came from. Expanding keybinding templates shifts
+  // the indices of later keybindings, so those can be attributed to the
+  // wrong file.
+  let merged_str = serde_yaml::to_string(&merged)?;
+
+  serde_yaml::from_str(&merged_str).map_err(|err| {
+    let (value_path, message) = split_error_path(&err.to_string());
+    let file = &files[origin_of(&value_path, &origins)];
+
+    let message = match value_path.is_empty() {
+      true => message,
+      false => format!("{}: {}", value_path, message),
+    };
+
+    file_error(
+      &file.path,
+      &file.source,
+      ConfigDiagnostic {
+        message,
+        location: None,
+      },
+    )
+    .into()
+  })
+}
+
+/// Indices of the given files in the order that they're merged in, where
+/// each file comes after the files that it includes.
+///
+/// Files are listed with each file followed by the files that it
+/// includes, so a file's includes are the files after it with a greater
+/// depth.
+pub fn merge_order(files: &[ConfigFile]) -> Vec<usize> {
+  let mut order = Vec::new();
+  let mut pending = Vec::<usize>::new();
+
+  for (index, file) in files.iter().enumerate() {
+    // Files at the same or a lower depth aren't included by the pending
+    // files, so any pending files at that depth are complete.
+    while pending
+      .last()
+      .is_some_and(|&last| files[last].depth >= file.depth)
+    {
+      order.extend(pending.pop());
+    }
+
+    pending.push(index);
+  }
+
+  order.extend(pending.into_iter().rev());
+  order
+}
+
+/// Merges a value from a config file into the merged config.
+///
+/// Records the index of the file that each overridden or appended value
+/// came from, keyed by the value's path (e.g. `window_rules[3]`).
+fn merge_value(
+  merged: &mut Value,
+  value: Value,
+  path: &str,
+  file_index: usize,
+  origins: &mut HashMap<String, usize>,
+) {
+  match (merged, value) {
+    (Value::Mapping(merged), Value::Mapping(mapping)) => {
+      for (key, value) in mapping {
+        let key_path = match (path, key.as_str()) {
+          (_, None) => path.to_string(),
+          ("", Some(key)) => key.to_string(),
+          (_, Some(key)) => format!("{}.{}", path, key),
+        };
+
+        match merged.get_mut(&key) {
+          Some(merged_value) => merge_value(
+            merged_value,
+            value,
+            &key_path,
+            file_index,
+            origins,
+          ),
+          None => {
+            origins.insert(key_path, file_index);
+            merged.insert(key, value);
+          }
+        }
+      }
+    }
+    (Value::Sequence(merged), Value::Sequence(sequence)) => {
+      for item in sequence {
+        origins.insert(format!("{}[{}]", path, merged.len()), file_index);
+        merged.push(item);
+      }
+    }
+    (merged, value) => {
+      origins.insert(path.to_string(), file_index);
+      *merged = value;
+    }
+  }
+}
+
+/// Index of the file that the value at the given path came from. Falls
+/// back to the main config file.
+fn origin_of(value_path: &str, origins: &HashMap<String, usize>) -> usize {
+  let mut path = value_path;
+
+  loop {
+    if let Some(index) = origins.get(path) {
+      return *index;
+    }
+
+    match path.rfind(['.', '[']) {
+      Some(index) => path = &path[..index],
+      None => return 0,
+    }
+  }
+}
+
+/// Splits a deserialization error into the path of the invalid value and
+/// the message. The location is dropped, since it refers to the merged
+/// config rather than to any one file.
+fn split_error_path(err: &str) -> (String, String) {
+  let err = match err.rsplit_once(" at line ") {
+    Some((err, _)) => err,
+    None => err,
+  };
+
+  match err.split_once(": ") {
+    Some((path, message))
+      if !path.is_empty() && !path.contains(char::is_whitespace) =>
+    {
+      (path.to_string(), message.to_string())
+    }
+    _ => (String::new(), err.to_string()),
+  }
+}
+
+fn invalid_include(path: &Path, source: &str) -> ConfigFileError {
+  file_error(
+    path,
+    source,
+    ConfigDiagnostic {
+      message: "`include` must be a list of paths.".to_string(),
+      location: None,
+    },
+  )
+}
+
+fn file_error(
+  path: &Path,
+  source: &str,
+  diagnostic: ConfigDiagnostic,
+) -> ConfigFileError {
+  ConfigFileError {
+    path: path.to_path_buf(),
+    source: source.to_string(),
+    diagnostic,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SAMPLE_CONFIG: &str =
+    include_str!("../../../resources/assets/sample-config.yaml");
+
+  fn config_file(path: &str, depth: usize, source: &str) -> ConfigFile {
+    ConfigFile {
+      path: PathBuf::from(path),
+      source: source.to_string(),
+      value: serde_yaml::from_str(source).unwrap(),
+      depth,
+      glob_dirs: Vec::new(),
+    }
+  }
+
+  /// Creates an empty directory to read config files from.
+  fn temp_config_dir() -> PathBuf {
+    let dir = std::env::temp_dir()
+      .join(format!("glazewm-test-{}", uuid::Uuid::new_v4()));
+
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  #[test]
+  fn merges_includes_before_includer() {
+    // `main` includes `a` and `d`, and `a` includes `b` and `c`.
+    let files = [
+      config_file("main.yaml", 0, "{}"),
+      config_file("a.yaml", 1, "{}"),
+      config_file("b.yaml", 2, "{}"),
+      config_file("c.yaml", 2, "{}"),
+      config_file("d.yaml", 1, "{}"),
+    ];
+
+    assert_eq!(merge_order(&files), [2, 3, 1, 4, 0]);
+  }
+
+  #[test]
+  fn appends_lists_and_overrides_scalars() {
+    let mut merged = Value::Mapping(Mapping::new());
+    let mut origins = HashMap::new();
+
+    let files = [
+      "general: { a: 1, b: 2 }\nkeybindings: [x]",
+      "general: { b: 3 }\nkeybindings: [y]",
+    ];
+
+    for (index, source) in files.iter().enumerate() {
+      let value = serde_yaml::from_str(source).unwrap();
+      merge_value(&mut merged, value, "", index, &mut origins);
+    }
+
+    assert_eq!(
+      merged,
+      serde_yaml::from_str::<Value>(
+        "general: { a: 1, b: 3 }\nkeybindings: [x, y]"
+      )
+      .unwrap()
+    );
+
+    assert_eq!(origins["general"], 0);
+    assert_eq!(origins["general.b"], 1);
+    assert_eq!(origins["keybindings"], 0);
+    assert_eq!(origins["keybindings[1]"], 1);
+  }
+
+  #[test]
+  fn finds_origin_of_nested_values() {
+    let origins = HashMap::from([
+      ("general".to_string(), 1),
+      ("window_rules[1]".to_string(), 2),
+    ]);
+
+    assert_eq!(origin_of("general.cursor_jump.enabled", &origins), 1);
+    assert_eq!(origin_of("window_rules[1].commands", &origins), 2);
+    assert_eq!(origin_of("window_rules[0].commands", &origins), 0);
+    assert_eq!(origin_of("gaps", &origins), 0);
+  }
+
+  #[test]
+  fn splits_error_path() {
+    assert_eq!(
+      split_error_path(
+        "window_rules[1].commands: Unknown command at line 3 column 5"
+      ),
+      (
+        "window_rules[1].commands".to_string(),
+        "Unknown command".to_string()
+      )
+    );
+
+    assert_eq!(
+      split_error_path("missing field `gaps` at line 1 column 1"),
+      (String::new(), "missing field `gaps`".to_string())
+    );
+
+    assert_eq!(
+      split_error_path("invalid type: string \"a\", expected a list"),
+      (
+        String::new(),
+        "invalid type: string \"a\", expected a list".to_string()
+      )
+    );
+  }
+
+  #[test]
+  fn attributes_errors_to_their_file() {
+    let included_rule = concat!(
+      "window_rules:\n",
+      "  - commands: ['not-a-command']\n",
+      "    match: [{ window_process: { equals: 'a' } }]\n",
+    );
+
+    let err = merge_config_files(&[
+      config_file("main.yaml", 0, SAMPLE_CONFIG),
+      config_file("rules.yaml", 1, included_rule),
+    ])
+    .unwrap_err()
+    .downcast::<ConfigFileError>()
+    .unwrap();
+
+    assert_eq!(err.path, Path::new("rules.yaml"));
+    assert!(err.diagnostic.message.starts_with("window_rules[0]: "));
+
+    let invalid_main = SAMPLE_CONFIG.replace(
+      "focus_follows_cursor: false",
+      "focus_follows_cursor: 'nope'",
+    );
+
+    let err = merge_config_files(&[
+      config_file("main.yaml", 0, &invalid_main),
+      config_file(
+        "rules.yaml",
+        1,
+        &included_rule.replace("not-a-command", "ignore"),
+      ),
+    ])
+    .unwrap_err()
+    .downcast::<ConfigFileError>()
+    .unwrap();
+
+    assert_eq!(err.path, Path::new("main.yaml"));
+    assert!(err
+      .diagnostic
+      .message
+      .starts_with("general.focus_follows_cursor: "));
+  }
+
+  #[test]
+  fn skips_include_cycles() {
+    let dir = temp_config_dir();
+    fs::write(dir.join("a.yaml"), "include: ['b.yaml']").unwrap();
+    fs::write(dir.join("b.yaml"), "include: ['a.yaml']").unwrap();
+
+    let files = read_config_files(&dir.join("a.yaml")).unwrap();
+
+    assert_eq!(
+      files
+        .iter()
+        .map(|file| (file.path.clone(), file.depth))
+        .collect::<Vec<_>>(),
+      [(dir.join("a.yaml"), 0), (dir.join("b.yaml"), 1)]
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn allows_globs_without_matches() {
+    let dir = temp_config_dir();
+    fs::write(dir.join("main.yaml"), "include: ['machines/*.yaml']")
+      .unwrap();
+
+    let files = read_config_files(&dir.join("main.yaml")).unwrap();
+
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].glob_dirs, [dir.join("machines")]);
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
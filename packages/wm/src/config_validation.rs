@@ -0,0 +1,907 @@
+use std::{
+  collections::{HashMap, HashSet},
+  fmt,
+  path::Path,
+};
+
+use serde_yaml::Value;
+use wm_common::{parse_command_chain, CommandSyntaxError, InvokeCommand};
+
+use crate::{
+  cli_output::{CliError, CliExitCode},
+  common::platform::KeyboardHook,
+  config_includes::{
+    merge_config_files, merge_order, read_config_files, ConfigFile,
+    ConfigFileError,
+  },
+  config_variables::{
+    config_variables, config_workspace_names, is_keybinding_template,
+    quote_variables, substitute_variables, workspace_variables,
+  },
+};
+
+/// Keys in the config whose values are lists of WM commands.
+pub const COMMAND_KEYS: [&str; 4] = [
+  "commands",
+  "startup_commands",
+  "shutdown_commands",
+  "config_reload_commands",
+];
+
+/// Problem found in a config file.
+#[derive(Clone, Debug)]
+pub struct ConfigDiagnostic {
+  pub message: String,
+  pub location: Option<SourceLocation>,
+}
+
+/// Position of a value within a config file.
+#[derive(Clone, Debug)]
+pub struct SourceLocation {
+  /// Line number (1-based).
+  pub line: usize,
+
+  /// Column number in characters (1-based).
+  pub column: usize,
+
+  /// Number of characters that the value spans.
+  pub length: usize,
+}
+
+impl ConfigDiagnostic {
+  /// Creates a diagnostic from a YAML parsing error.
+  pub fn from_yaml_error(err: &serde_yaml::Error, source: &str) -> Self {
+    let message = err.to_string();
+
+    // The location is already part of the diagnostic, so it's trimmed
+    // from the error message.
+    let message = match message.rsplit_once(" at line ") {
+      Some((message, _)) => message.to_string(),
+      None => message,
+    };
+
+    let location = err.location().map(|location| {
+      // Highlight the rest of the line, since the length of the invalid
+      // value isn't known.
+      let length = source
+        .lines()
+        .nth(location.line().saturating_sub(1))
+        .map(|line| {
+          line
+            .chars()
+            .skip(location.column().saturating_sub(1))
+            .collect::<String>()
+            .trim_end()
+            .chars()
+            .count()
+        })
+        .unwrap_or(1)
+        .max(1);
+
+      SourceLocation {
+        line: location.line(),
+        column: location.column(),
+        length,
+      }
+    });
+
+    Self { message, location }
+  }
+
+  /// Formats the diagnostic with the file path, location and a snippet
+  /// of the offending line.
+  pub fn render(&self, path: &Path, source: &str) -> String {
+    let Some(location) = &self.location else {
+      return format!("error: {}\n  --> {}", self.message, path.display());
+    };
+
+    let line_number = location.line.to_string();
+    let gutter = " ".repeat(line_number.len());
+    let snippet = source
+      .lines()
+      .nth(location.line.saturating_sub(1))
+      .unwrap_or("");
+
+    format!(
+      "error: {}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}",
+      self.message,
+      gutter,
+      path.display(),
+      location.line,
+      location.column,
+      gutter,
+      line_number,
+      snippet,
+      gutter,
+      " ".repeat(location.column.saturating_sub(1)),
+      "^".repeat(location.length),
+    )
+  }
+}
+
+impl fmt::Display for ConfigDiagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.location {
+      Some(location) => write!(
+        f,
+        "{} (line {}, column {})",
+        self.message, location.line, location.column
+      ),
+      None => write!(f, "{}", self.message),
+    }
+  }
+}
+
+/// Validates the config file at the given path, along with any files
+/// that it includes, and outputs any errors to stderr.
+///
+/// Used by the `validate-config` command, which exits with
+/// `CliExitCode::InvalidConfig` if the config is invalid.
+pub fn validate_config_path(config_path: &Path) -> Result<(), CliError> {
+  let errors = match read_config_files(config_path) {
+    Ok(files) => validate_config_files(&files),
+    Err(err) => match err.downcast::<ConfigFileError>() {
+      Ok(file_err) => vec![file_err],
+      Err(err) => {
+        return Err(CliError::new(CliExitCode::InvalidConfig, err))
+      }
+    },
+  };
+
+  for err in &errors {
+    eprintln!("{}\n", err.render());
+  }
+
+  let message = match errors.len() {
+    0 => {
+      println!("Config file {} is valid.", config_path.display());
+      return Ok(());
+    }
+    1 => "Found 1 error in config file.".to_string(),
+    count => format!("Found {} errors in config file.", count),
+  };
+
+  Err(CliError::new(
+    CliExitCode::InvalidConfig,
+    anyhow::anyhow!(message),
+  ))
+}
+
+/// Validates the config files that make up the user config without a
+/// running WM instance.
+///
+/// Each file is checked on its own, followed by the structure of the
+/// merged config. Returns all problems that were found, or an empty
+/// vector if the config is valid.
+pub fn validate_config_files(
+  files: &[ConfigFile],
+) -> Vec<ConfigFileError> {
+  // Variables and workspaces can be defined in any of the files. They're
+  // read in merge order, such that they match the merged config.
+  let mut variables = HashMap::new();
+  let mut workspace_names = Vec::new();
+
+  for index in merge_order(files) {
+    let value = &files[index].value;
+    variables.extend(config_variables(value).unwrap_or_default());
+    workspace_names.extend(config_workspace_names(value));
+  }
+
+  // Keybinding templates are checked with each workspace.
+  let template_variables = workspace_names
+    .iter()
+    .enumerate()
+    .map(|(index, name)| workspace_variables(name, index))
+    .collect::<Vec<_>>();
+
+  // Workspace names are shared across files, such that duplicates in
+  // different files are caught.
+  let mut seen_workspace_names = HashSet::new();
+
+  let mut errors = files
+    .iter()
+    .flat_map(|file| {
+      validate_config_values(
+        &file.source,
+        &variables,
+        &template_variables,
+        &mut seen_workspace_names,
+      )
+      .into_iter()
+      .map(|diagnostic| ConfigFileError {
+        path: file.path.clone(),
+        source: file.source.clone(),
+        diagnostic,
+      })
+    })
+    .collect::<Vec<_>>();
+
+  // Check the structure of the merged config last. Deserialization stops
+  // at the first error, which might already have been reported (e.g. an
+  // invalid command).
+  if let Err(err) = merge_config_files(files) {
+    let err = match err.downcast::<ConfigFileError>() {
+      Ok(file_err) => file_err,
+      Err(err) => ConfigFileError {
+        path: files
+          .first()
+          .map(|file| file.path.clone())
+          .unwrap_or_default(),
+        source: String::new(),
+        diagnostic: ConfigDiagnostic {
+          message: err.to_string(),
+          location: None,
+        },
+      },
+    };
+
+    let is_reported = match &err.diagnostic.location {
+      Some(location) => errors.iter().any(|reported| {
+        reported.path == err.path
+          && reported
+            .diagnostic
+            .location
+            .as_ref()
+            .is_some_and(|reported| reported.line == location.line)
+      }),
+      None => !errors.is_empty(),
+    };
+
+    if !is_reported {
+      errors.push(err);
+    }
+  }
+
+  errors
+}
+
+/// Validates the values within a config file (e.g. commands and regexes),
+/// without checking that the file is a complete config.
+fn validate_config_values(
+  source: &str,
+  variables: &HashMap<String, String>,
+  template_variables: &[HashMap<String, String>],
+  workspace_names: &mut HashSet<String>,
+) -> Vec<ConfigDiagnostic> {
+  let value = match serde_yaml::from_str::<Value>(source) {
+    Ok(value) => value,
+    Err(err) => {
+      return vec![ConfigDiagnostic::from_yaml_error(&err, source)];
+    }
+  };
+
+  let mut validator = ConfigValidator {
+    source,
+    cursor: 0,
+    variables,
+    template_variables,
+    is_in_template: false,
+    workspace_names,
+    diagnostics: Vec::new(),
+  };
+
+  validator.visit(&value, "", None);
+
+  validator.diagnostics
+}
+
+/// Walks the parsed YAML in document order and checks the values that
+/// deserialization doesn't fully validate.
+///
+/// `serde_yaml` doesn't expose the location of parsed values, so they are
+/// found by searching the source text from the previously found value.
+struct ConfigValidator<'a> {
+  source: &'a str,
+
+  /// Byte offset in the source after the most recently found value.
+  cursor: usize,
+
+  /// Variables to substitute into bindings and commands before checking
+  /// them.
+  variables: &'a HashMap<String, String>,
+
+  /// Variables of each workspace, for expanding keybinding templates.
+  template_variables: &'a [HashMap<String, String>],
+
+  /// Whether the values being visited are within a keybinding template.
+  is_in_template: bool,
+
+  /// Names of workspaces seen so far, including in previously validated
+  /// files, for catching duplicates.
+  workspace_names: &'a mut HashSet<String>,
+
+  diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl ConfigValidator<'_> {
+  fn visit(&mut self, value: &Value, path: &str, key: Option<&str>) {
+    match value {
+      Value::Mapping(mapping) => {
+        let was_in_template = self.is_in_template;
+        self.is_in_template |= is_keybinding_template(value);
+
+        for (child_key, child_value) in mapping {
+          let child_key = scalar_to_string(child_key);
+
+          if let Some(child_key) = &child_key {
+            self.locate(child_key);
+          }
+
+          let child_path = match (path, &child_key) {
+            ("", Some(child_key)) => child_key.clone(),
+            (_, Some(child_key)) => format!("{}.{}", path, child_key),
+            (_, None) => path.to_string(),
+          };
+
+          self.visit(child_value, &child_path, child_key.as_deref());
+        }
+
+        self.is_in_template = was_in_template;
+      }
+      // Items of a sequence are checked based on the key of the
+      // sequence (e.g. each item under `commands`).
+      Value::Sequence(sequence) => {
+        for (index, item) in sequence.iter().enumerate() {
+          self.visit(item, &format!("{}[{}]", path, index), key);
+        }
+      }
+      Value::Tagged(tagged) => self.visit(&tagged.value, path, key),
+      _ => {
+        if let Some(text) = scalar_to_string(value) {
+          let location = self.locate(&text);
+          self.check_scalar(&text, path, key, location);
+        }
+      }
+    }
+  }
+
+  fn check_scalar(
+    &mut self,
+    text: &str,
+    path: &str,
+    key: Option<&str>,
+    location: Option<SourceLocation>,
+  ) {
+    if key.is_some_and(|key| COMMAND_KEYS.contains(&key)) {
+      return self.check_commands(text, path, location);
+    }
+
+    let message = match key {
+      Some("regex" | "not_regex") => {
+        regex::Regex::new(text).err().map(|err| {
+          // Regex errors span multiple lines, where the last line
+          // describes the problem.
+          let err = err.to_string();
+          let reason = err.lines().last().unwrap_or_default();
+          format!(
+            "Invalid regex '{}': {}",
+            text,
+            reason.trim_start_matches("error: ")
+          )
+        })
+      }
+      Some("bindings") => {
+        self.expand(text, false).into_iter().find_map(|binding| {
+          binding
+            .split('+')
+            .find(|binding_key| {
+              KeyboardHook::key_to_vk_code(binding_key).is_none()
+            })
+            .map(|binding_key| {
+              format!(
+                "Unrecognized key '{}' in binding '{}'. Ensure that alt or shift isn't required for the key.",
+                binding_key, binding
+              )
+            })
+        })
+      }
+      Some("name") if is_workspace_name_path(path) => {
+        (!self.workspace_names.insert(text.to_string()))
+          .then(|| format!("Duplicate workspace name '{}'.", text))
+      }
+      _ => None,
+    };
+
+    if let Some(message) = message {
+      self.diagnostics.push(ConfigDiagnostic {
+        message: format!("{}: {}", path, message),
+        location,
+      });
+    }
+  }
+
+  /// Checks a command string, which can hold multiple commands chained
+  /// with `;` or `&&`.
+  fn check_commands(
+    &mut self,
+    text: &str,
+    path: &str,
+    location: Option<SourceLocation>,
+  ) {
+    let error = self.expand(text, true).into_iter().find_map(|command| {
+      let result = parse_command_chain(&command)
+        .map_err(anyhow::Error::from)
+        .and_then(|segments| {
+          segments.iter().try_for_each(|segment| {
+            InvokeCommand::from_args(&command, &segment.args).map(|_| ())
+          })
+        });
+
+      result.err().map(|err| (command, err))
+    });
+
+    let Some((command, err)) = error else {
+      return;
+    };
+
+    // Narrow down the location to the offending part of the command.
+    // Positions within a substituted command or a command with escaped
+    // characters don't match the source.
+    let is_verbatim = command == text
+      && location
+        .as_ref()
+        .is_some_and(|location| location.length == text.chars().count());
+
+    let (message, location) =
+      match err.downcast_ref::<CommandSyntaxError>() {
+        Some(syntax_err) if is_verbatim => (
+          syntax_err.message.clone(),
+          location.map(|location| SourceLocation {
+            line: location.line,
+            column: location.column + syntax_err.position,
+            length: syntax_err.length,
+          }),
+        ),
+        Some(syntax_err) => (syntax_err.message.clone(), location),
+        None => (err.to_string(), location),
+      };
+
+    self.diagnostics.push(ConfigDiagnostic {
+      message: format!(
+        "{}: Invalid command '{}': {}",
+        path, command, message
+      ),
+      location,
+    });
+  }
+
+  /// Substitutes variables into a binding or command. Within keybinding
+  /// templates, the text is expanded for each workspace, same as when
+  /// the config is loaded.
+  fn expand(&self, text: &str, is_command: bool) -> Vec<String> {
+    if !self.is_in_template {
+      return vec![substitute_variables(text, self.variables)];
+    }
+
+    self
+      .template_variables
+      .iter()
+      .map(|workspace_variables| {
+        let text = match is_command {
+          true => substitute_variables(
+            text,
+            &quote_variables(workspace_variables),
+          ),
+          false => substitute_variables(text, workspace_variables),
+        };
+
+        substitute_variables(&text, self.variables)
+      })
+      .collect()
+  }
+
+  /// Finds the next occurrence of the given scalar that isn't within a
+  /// comment, and moves the cursor past it.
+  ///
+  /// Parsed scalars have their quotes and escapes removed, so the scalar
+  /// is searched for as written plainly, in single quotes and in double
+  /// quotes. The location spans the scalar without its quotes.
+  fn locate(&mut self, text: &str) -> Option<SourceLocation> {
+    if text.is_empty() {
+      return None;
+    }
+
+    let candidates = [
+      (text.to_string(), None),
+      (text.replace('\'', "''"), Some('\'')),
+      (escape_double_quoted(text), Some('"')),
+    ];
+
+    let (start, end, quote) = candidates
+      .iter()
+      .filter_map(|(written, quote)| {
+        self
+          .find_scalar(written, *quote)
+          .map(|(start, end)| (start, end, quote))
+      })
+      .min_by_key(|(start, ..)| *start)?;
+
+    self.cursor = end;
+
+    // Exclude the quotes from the location.
+    let (start, end) = match quote {
+      Some(quote) => (start + quote.len_utf8(), end - quote.len_utf8()),
+      None => (start, end),
+    };
+
+    let line_start = self.source[..start]
+      .rfind('\n')
+      .map_or(0, |index| index + 1);
+
+    Some(SourceLocation {
+      line: self.source[..start].matches('\n').count() + 1,
+      column: self.source[line_start..start].chars().count() + 1,
+      length: self.source[start..end].chars().count(),
+    })
+  }
+
+  /// Finds the next occurrence of a scalar as written in the source,
+  /// optionally wrapped in the given quote. Returns the byte range of the
+  /// scalar, including any quotes.
+  fn find_scalar(
+    &self,
+    written: &str,
+    quote: Option<char>,
+  ) -> Option<(usize, usize)> {
+    let needle = match quote {
+      Some(quote) => format!("{}{}{}", quote, written, quote),
+      None => written.to_string(),
+    };
+
+    let mut search_start = self.cursor;
+
+    while let Some(offset) = self.source[search_start..].find(&needle) {
+      let start = search_start + offset;
+      let end = start + needle.len();
+
+      let line_start = self.source[..start]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+
+      // Plain scalars mustn't be part of a longer scalar (e.g. `1` within
+      // `10`).
+      let is_whole_scalar = quote.is_some()
+        || (self.source[..start].chars().next_back().is_none_or(|char| {
+          char.is_whitespace() || matches!(char, '[' | '{' | ',')
+        }) && self.source[end..].chars().next().is_none_or(|char| {
+          char.is_whitespace() || matches!(char, ',' | ']' | '}' | ':')
+        }));
+
+      if is_whole_scalar && !is_in_comment(&self.source[line_start..start])
+      {
+        return Some((start, end));
+      }
+
+      search_start =
+        start + needle.chars().next().map_or(1, char::len_utf8);
+    }
+
+    None
+  }
+}
+
+/// Escapes a string as it would be written within double quotes in YAML.
+fn escape_double_quoted(text: &str) -> String {
+  text
+    .replace('\\', r"\\")
+    .replace('"', r#"\""#)
+    .replace('\n', r"\n")
+    .replace('\t', r"\t")
+}
+
+/// Whether the end of the given line prefix is within a comment.
+///
+/// Comments start with a `#` at the start of the line or after
+/// whitespace, outside of quotes.
+fn is_in_comment(line_prefix: &str) -> bool {
+  let mut quote = None;
+  let mut prev_char = None;
+
+  for char in line_prefix.chars() {
+    let is_token_start = prev_char.is_none_or(|prev_char: char| {
+      prev_char.is_whitespace() || matches!(prev_char, '[' | '{' | ',')
+    });
+
+    match (quote, char) {
+      (None, '#') if is_token_start => return true,
+      (None, '\'' | '"') if is_token_start => quote = Some(char),
+      (Some('"'), '"') if prev_char == Some('\\') => {}
+      (Some(open_quote), char) if char == open_quote => quote = None,
+      _ => {}
+    }
+
+    prev_char = Some(char);
+  }
+
+  false
+}
+
+/// Whether the path points to the name of a workspace (e.g.
+/// `workspaces[2].name`).
+fn is_workspace_name_path(path: &str) -> bool {
+  path
+    .strip_prefix("workspaces[")
+    .and_then(|rest| rest.split_once("]."))
+    .is_some_and(|(index, key)| {
+      key == "name" && index.parse::<usize>().is_ok()
+    })
+}
+
+/// String representation of a scalar YAML value. Returns `None` for
+/// null values and collections.
+fn scalar_to_string(value: &Value) -> Option<String> {
+  match value {
+    Value::String(string) => Some(string.clone()),
+    Value::Number(number) => Some(number.to_string()),
+    Value::Bool(value) => Some(value.to_string()),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{fs, path::PathBuf};
+
+  use super::*;
+
+  const SAMPLE_CONFIG: &str =
+    include_str!("../../../resources/assets/sample-config.yaml");
+
+  /// Line, column and length of a located value.
+  type Location = (usize, usize, usize);
+
+  /// Validates a single config file, returning the message and location
+  /// of each diagnostic.
+  fn validate(source: &str) -> Vec<(String, Option<Location>)> {
+    validate_config_values(
+      source,
+      &HashMap::new(),
+      &[],
+      &mut HashSet::new(),
+    )
+    .into_iter()
+    .map(|diagnostic| {
+      let location = diagnostic
+        .location
+        .map(|location| (location.line, location.column, location.length));
+
+      (diagnostic.message, location)
+    })
+    .collect()
+  }
+
+  /// Locates the given scalars in order, returning their line, column
+  /// and length.
+  fn locate_all(
+    source: &str,
+    texts: &[&str],
+  ) -> Vec<Option<(usize, usize, usize)>> {
+    let variables = HashMap::new();
+    let mut workspace_names = HashSet::new();
+
+    let mut validator = ConfigValidator {
+      source,
+      cursor: 0,
+      variables: &variables,
+      template_variables: &[],
+      is_in_template: false,
+      workspace_names: &mut workspace_names,
+      diagnostics: Vec::new(),
+    };
+
+    texts
+      .iter()
+      .map(|text| {
+        validator.locate(text).map(|location| {
+          (location.line, location.column, location.length)
+        })
+      })
+      .collect()
+  }
+
+  #[test]
+  fn locates_plain_scalars() {
+    let source = "general:\n  commands: [focus --workspace 1]\n";
+
+    assert_eq!(
+      locate_all(source, &["general", "commands", "focus --workspace 1"]),
+      [Some((1, 1, 7)), Some((2, 3, 8)), Some((2, 14, 19))]
+    );
+  }
+
+  #[test]
+  fn locates_quoted_scalars() {
+    let source = concat!(
+      "a: 'it''s'\n",
+      "b: \"say \\\"hi\\\"\"\n",
+      "c: \"C:\\\\path\"\n",
+    );
+
+    assert_eq!(
+      locate_all(
+        source,
+        &["a", "it's", "b", "say \"hi\"", "c", "C:\\path"]
+      ),
+      [
+        Some((1, 1, 1)),
+        Some((1, 5, 5)),
+        Some((2, 1, 1)),
+        Some((2, 5, 10)),
+        Some((3, 1, 1)),
+        Some((3, 5, 8)),
+      ]
+    );
+  }
+
+  #[test]
+  fn skips_comments() {
+    let source = "# name: '1'\nname: '1' # name: '1'\ntitle: 'a # b'\n";
+
+    assert_eq!(
+      locate_all(source, &["name", "1", "title", "a # b"]),
+      [
+        Some((2, 1, 4)),
+        Some((2, 8, 1)),
+        Some((3, 1, 5)),
+        Some((3, 9, 5))
+      ]
+    );
+  }
+
+  #[test]
+  fn skips_partial_matches() {
+    let source =
+      "workspaces:\n  - name: 10\n  - name: \"x 1\"\n  - name: 1\n";
+
+    assert_eq!(
+      locate_all(
+        source,
+        &["workspaces", "name", "10", "name", "x 1", "name", "1"]
+      ),
+      [
+        Some((1, 1, 10)),
+        Some((2, 5, 4)),
+        Some((2, 11, 2)),
+        Some((3, 5, 4)),
+        Some((3, 12, 3)),
+        Some((4, 5, 4)),
+        Some((4, 11, 1)),
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_invalid_commands() {
+    let source = concat!(
+      "general:\n",
+      "  startup_commands: ['focus --workspace 1', 'foo bar']\n",
+      "keybindings:\n",
+      "  - commands: ['focus --workspace 1 &&']\n",
+      "    bindings: ['alt+1']\n",
+    );
+
+    let diagnostics = validate(source);
+    assert_eq!(diagnostics.len(), 2);
+
+    // Errors from clap point to the offending argument.
+    let (message, location) = &diagnostics[0];
+    assert!(message.starts_with(
+      "general.startup_commands[1]: Invalid command 'foo bar': "
+    ));
+    assert_eq!(*location, Some((2, 46, 3)));
+
+    // Syntax errors point to the offending part of the command.
+    assert_eq!(
+      diagnostics[1],
+      (
+        "keybindings[0].commands[0]: Invalid command 'focus --workspace \
+         1 &&': Expected a command after `&&`."
+          .to_string(),
+        Some((4, 39, 1))
+      )
+    );
+  }
+
+  #[test]
+  fn reports_invalid_regexes() {
+    let source = concat!(
+      "window_rules:\n",
+      "  - commands: ['ignore']\n",
+      "    match:\n",
+      "      - window_title: { regex: 'a(b' }\n",
+      "      - window_class: { not_regex: 'c' }\n",
+    );
+
+    let diagnostics = validate(source);
+    assert_eq!(diagnostics.len(), 1);
+
+    let (message, location) = &diagnostics[0];
+    assert!(message.starts_with(
+      "window_rules[0].match[0].window_title.regex: Invalid regex 'a(b': "
+    ));
+    assert_eq!(*location, Some((4, 33, 3)));
+  }
+
+  #[test]
+  fn reports_unrecognized_keys() {
+    let source = concat!(
+      "keybindings:\n",
+      "  - commands: ['focus --workspace 1']\n",
+      "    bindings: ['alt+1', 'alt+foo']\n",
+    );
+
+    assert_eq!(
+      validate(source),
+      [(
+        "keybindings[0].bindings[1]: Unrecognized key 'foo' in binding \
+         'alt+foo'. Ensure that alt or shift isn't required for the key."
+          .to_string(),
+        Some((3, 26, 7))
+      )]
+    );
+  }
+
+  #[test]
+  fn reports_duplicate_workspace_names() {
+    let source = concat!(
+      "workspaces:\n",
+      "  - name: '1'\n",
+      "  - name: 2\n",
+      "  - name: 1\n",
+    );
+
+    assert_eq!(
+      validate(source),
+      [(
+        "workspaces[2].name: Duplicate workspace name '1'.".to_string(),
+        Some((4, 11, 1))
+      )]
+    );
+  }
+
+  #[test]
+  fn reports_duplicate_workspace_names_across_files() {
+    let file = |path: &str, source: &str| ConfigFile {
+      path: PathBuf::from(path),
+      source: source.to_string(),
+      value: serde_yaml::from_str(source).unwrap(),
+      depth: usize::from(path != "main.yaml"),
+      glob_dirs: Vec::new(),
+    };
+
+    let errors = validate_config_files(&[
+      file("main.yaml", "workspaces:\n  - name: '1'\n"),
+      file("extra.yaml", "workspaces:\n  - name: '1'\n"),
+    ]);
+
+    assert_eq!(errors[0].path, Path::new("extra.yaml"));
+    assert_eq!(
+      errors[0].diagnostic.message,
+      "workspaces[0].name: Duplicate workspace name '1'."
+    );
+  }
+
+  #[test]
+  fn exits_with_invalid_config_code() {
+    let dir = std::env::temp_dir()
+      .join(format!("glazewm-test-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let config_path = dir.join("config.yaml");
+    fs::write(&config_path, SAMPLE_CONFIG).unwrap();
+    assert!(validate_config_path(&config_path).is_ok());
+
+    let invalid_config =
+      SAMPLE_CONFIG.replace("commands: ['ignore']", "commands: ['foo']");
+    fs::write(&config_path, invalid_config).unwrap();
+
+    let err = validate_config_path(&config_path).unwrap_err();
+    assert_eq!(err.exit_code, CliExitCode::InvalidConfig);
+    assert_eq!(err.exit_code as i32, 5);
+
+    let err = validate_config_path(&dir.join("missing.yaml")).unwrap_err();
+    assert_eq!(err.exit_code, CliExitCode::InvalidConfig);
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -0,0 +1,421 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use serde_yaml::{Mapping, Value};
+use wm_common::quote_command_arg;
+
+use crate::config_validation::COMMAND_KEYS;
+
+/// Key of the mapping of user-defined variables.
+pub const VARIABLES_KEY: &str = "variables";
+
+/// Key that marks a keybinding as a template to repeat for each
+/// workspace.
+const FOR_EACH_WORKSPACE_KEY: &str = "for_each_workspace";
+
+/// Variables that are set for each workspace when expanding keybinding
+/// templates. These can't be used as names of user-defined variables.
+const WORKSPACE_VARIABLES: [&str; 2] = ["workspace", "workspace_index"];
+
+/// Reads the user-defined variables of a config.
+///
+/// Names can be written with or without a leading `$` (e.g. `$mod: alt`
+/// or `mod: alt`).
+pub fn config_variables(
+  config: &Value,
+) -> anyhow::Result<HashMap<String, String>> {
+  let variables = match config.get(VARIABLES_KEY) {
+    None | Some(Value::Null) => return Ok(HashMap::new()),
+    Some(Value::Mapping(variables)) => variables,
+    Some(_) => bail!("`variables` must be a mapping of names to values."),
+  };
+
+  let mut result = HashMap::new();
+
+  for (name, value) in variables {
+    let Some(name) = name.as_str() else {
+      bail!("Variable names must be strings.");
+    };
+
+    let name = name.trim_start_matches('$');
+
+    if name.is_empty()
+      || !name
+        .chars()
+        .all(|char| char.is_ascii_alphanumeric() || char == '_')
+    {
+      bail!(
+        "Invalid variable name '{}'. Use letters, digits or underscores.",
+        name
+      );
+    }
+
+    if WORKSPACE_VARIABLES.contains(&name) {
+      bail!(
+        "Variable name '{}' is reserved for keybinding templates.",
+        name
+      );
+    }
+
+    let value = match value {
+      Value::String(value) => value.clone(),
+      Value::Number(value) => value.to_string(),
+      Value::Bool(value) => value.to_string(),
+      _ => {
+        bail!("Variable '{}' must be a string, number or boolean.", name)
+      }
+    };
+
+    result.insert(name.to_string(), value);
+  }
+
+  Ok(result)
+}
+
+/// Expands keybinding templates and substitutes variables into bindings
+/// and commands.
+///
+/// Returns whether the config was changed.
+pub fn expand_config(config: &mut Value) -> anyhow::Result<bool> {
+  let variables = config_variables(config)?;
+
+  // Normalize the variables, so that they deserialize as a map of
+  // strings.
+  if let Some(Value::Mapping(mapping)) = config.get_mut(VARIABLES_KEY) {
+    *mapping = variables
+      .iter()
+      .map(|(name, value)| {
+        (Value::String(name.clone()), Value::String(value.clone()))
+      })
+      .collect::<Mapping>();
+  }
+
+  let workspace_names = config_workspace_names(config);
+  let mut is_expanded = false;
+
+  if let Some(keybindings) = config.get_mut("keybindings") {
+    is_expanded |= expand_keybindings(keybindings, &workspace_names);
+  }
+
+  if let Some(Value::Sequence(binding_modes)) =
+    config.get_mut("binding_modes")
+  {
+    for binding_mode in binding_modes {
+      if let Some(keybindings) = binding_mode.get_mut("keybindings") {
+        is_expanded |= expand_keybindings(keybindings, &workspace_names);
+      }
+    }
+  }
+
+  if !variables.is_empty() {
+    substitute_in_value(config, None, &variables, &variables);
+  }
+
+  Ok(is_expanded || !variables.is_empty())
+}
+
+/// Replaces `$name` and `${name}` with the values of the given
+/// variables.
+///
+/// Unknown variables are left as is, since commands can contain `$` for
+/// other purposes (e.g. PowerShell variables in `shell-exec`).
+pub fn substitute_variables(
+  text: &str,
+  variables: &HashMap<String, String>,
+) -> String {
+  let mut result = String::with_capacity(text.len());
+  let mut rest = text;
+
+  while let Some(index) = rest.find('$') {
+    result.push_str(&rest[..index]);
+    let after = &rest[index + 1..];
+
+    // Length of the variable name, including any braces.
+    let (name, length) = match after.strip_prefix('{') {
+      Some(braced) => match braced.find('}') {
+        Some(end) => (&braced[..end], end + 2),
+        None => ("", 0),
+      },
+      None => {
+        let end = after
+          .find(|char: char| !char.is_ascii_alphanumeric() && char != '_')
+          .unwrap_or(after.len());
+
+        (&after[..end], end)
+      }
+    };
+
+    match variables.get(name) {
+      Some(value) if !name.is_empty() => {
+        result.push_str(value);
+        rest = &after[length..];
+      }
+      _ => {
+        result.push('$');
+        rest = after;
+      }
+    }
+  }
+
+  result.push_str(rest);
+  result
+}
+
+/// Variables for expanding a keybinding template with the given
+/// workspace.
+pub fn workspace_variables(
+  workspace_name: &str,
+  index: usize,
+) -> HashMap<String, String> {
+  HashMap::from([
+    (
+      WORKSPACE_VARIABLES[0].to_string(),
+      workspace_name.to_string(),
+    ),
+    (WORKSPACE_VARIABLES[1].to_string(), (index + 1).to_string()),
+  ])
+}
+
+/// Quotes the values of the given variables, for substituting them into
+/// commands as single arguments (e.g. a workspace name with spaces).
+pub fn quote_variables(
+  variables: &HashMap<String, String>,
+) -> HashMap<String, String> {
+  variables
+    .iter()
+    .map(|(name, value)| {
+      (name.clone(), quote_command_arg(value).into_owned())
+    })
+    .collect()
+}
+
+/// Names of the workspaces in the config, in order.
+pub fn config_workspace_names(config: &Value) -> Vec<String> {
+  let Some(Value::Sequence(workspaces)) = config.get("workspaces") else {
+    return Vec::new();
+  };
+
+  workspaces
+    .iter()
+    .filter_map(|workspace| match workspace.get("name") {
+      Some(Value::String(name)) => Some(name.clone()),
+      Some(Value::Number(name)) => Some(name.to_string()),
+      _ => None,
+    })
+    .collect()
+}
+
+/// Whether the given keybinding is a template to repeat for each
+/// workspace.
+pub fn is_keybinding_template(keybinding: &Value) -> bool {
+  keybinding
+    .get(FOR_EACH_WORKSPACE_KEY)
+    .and_then(Value::as_bool)
+    == Some(true)
+}
+
+/// Replaces each keybinding template with a keybinding per workspace.
+///
+/// Returns whether any templates were expanded.
+fn expand_keybindings(
+  keybindings: &mut Value,
+  workspace_names: &[String],
+) -> bool {
+  let Value::Sequence(keybindings) = keybindings else {
+    return false;
+  };
+
+  if !keybindings.iter().any(is_keybinding_template) {
+    return false;
+  }
+
+  let mut expanded = Vec::new();
+
+  for keybinding in keybindings.drain(..) {
+    if !is_keybinding_template(&keybinding) {
+      expanded.push(keybinding);
+      continue;
+    }
+
+    for (index, workspace_name) in workspace_names.iter().enumerate() {
+      let mut workspace_keybinding = keybinding.clone();
+
+      if let Value::Mapping(mapping) = &mut workspace_keybinding {
+        mapping.remove(FOR_EACH_WORKSPACE_KEY);
+      }
+
+      let variables = workspace_variables(workspace_name, index);
+
+      substitute_in_value(
+        &mut workspace_keybinding,
+        None,
+        &variables,
+        &quote_variables(&variables),
+      );
+
+      expanded.push(workspace_keybinding);
+    }
+  }
+
+  *keybindings = expanded;
+  true
+}
+
+/// Substitutes variables into the bindings and commands within the given
+/// value. Commands are substituted with `command_variables` instead.
+fn substitute_in_value(
+  value: &mut Value,
+  key: Option<&str>,
+  variables: &HashMap<String, String>,
+  command_variables: &HashMap<String, String>,
+) {
+  match value {
+    Value::Mapping(mapping) => {
+      for (child_key, child_value) in mapping.iter_mut() {
+        substitute_in_value(
+          child_value,
+          child_key.as_str(),
+          variables,
+          command_variables,
+        );
+      }
+    }
+    Value::Sequence(sequence) => {
+      for item in sequence {
+        substitute_in_value(item, key, variables, command_variables);
+      }
+    }
+    Value::String(text) if key.is_some_and(is_substituted_key) => {
+      let variables =
+        match key.is_some_and(|key| COMMAND_KEYS.contains(&key)) {
+          true => command_variables,
+          false => variables,
+        };
+
+      *text = substitute_variables(text, variables);
+    }
+    _ => {}
+  }
+}
+
+/// Whether variables are substituted into values of the given key.
+pub fn is_substituted_key(key: &str) -> bool {
+  key == "bindings" || COMMAND_KEYS.contains(&key)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn variables(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+      .iter()
+      .map(|(name, value)| (name.to_string(), value.to_string()))
+      .collect()
+  }
+
+  fn yaml(source: &str) -> Value {
+    serde_yaml::from_str(source).unwrap()
+  }
+
+  #[test]
+  fn substitutes_plain_and_braced_names() {
+    let variables = variables(&[("mod", "alt"), ("dir", "left")]);
+
+    assert_eq!(substitute_variables("$mod+h", &variables), "alt+h");
+    assert_eq!(
+      substitute_variables("focus --direction ${dir}most", &variables),
+      "focus --direction leftmost"
+    );
+    assert_eq!(substitute_variables("$mod$dir", &variables), "altleft");
+  }
+
+  #[test]
+  fn keeps_unknown_variables() {
+    let variables = variables(&[("mod", "alt")]);
+
+    assert_eq!(
+      substitute_variables("shell-exec echo $env:USERPROFILE", &variables),
+      "shell-exec echo $env:USERPROFILE"
+    );
+    assert_eq!(
+      substitute_variables("${unknown} ${mod", &variables),
+      "${unknown} ${mod"
+    );
+    assert_eq!(substitute_variables("$modifier", &variables), "$modifier");
+  }
+
+  #[test]
+  fn keeps_trailing_dollar_sign() {
+    let variables = variables(&[("mod", "alt")]);
+
+    assert_eq!(substitute_variables("cost: 5$", &variables), "cost: 5$");
+    assert_eq!(substitute_variables("$", &variables), "$");
+    assert_eq!(substitute_variables("$mod$", &variables), "alt$");
+  }
+
+  #[test]
+  fn quotes_workspace_names_in_commands() {
+    let mut config = yaml(concat!(
+      "workspaces:\n",
+      "  - name: 'my files'\n",
+      "  - name: 2\n",
+      "keybindings:\n",
+      "  - commands: ['focus --workspace $workspace']\n",
+      "    bindings: ['alt+${workspace_index}']\n",
+      "    for_each_workspace: true\n",
+    ));
+
+    assert!(expand_config(&mut config).unwrap());
+
+    assert_eq!(
+      config["keybindings"],
+      yaml(concat!(
+        "- commands: [\"focus --workspace 'my files'\"]\n",
+        "  bindings: ['alt+1']\n",
+        "- commands: ['focus --workspace 2']\n",
+        "  bindings: ['alt+2']\n",
+      ))
+    );
+  }
+
+  #[test]
+  fn substitutes_user_variables_unquoted() {
+    let mut config = yaml(concat!(
+      "variables:\n",
+      "  $mod: alt\n",
+      "  terminal: 'wt -p Ubuntu'\n",
+      "keybindings:\n",
+      "  - commands: ['shell-exec $terminal']\n",
+      "    bindings: ['$mod+enter']\n",
+    ));
+
+    assert!(expand_config(&mut config).unwrap());
+
+    assert_eq!(
+      config["keybindings"],
+      yaml(concat!(
+        "- commands: ['shell-exec wt -p Ubuntu']\n",
+        "  bindings: ['alt+enter']\n",
+      ))
+    );
+  }
+
+  #[test]
+  fn rejects_invalid_variable_names() {
+    for (name, error) in [
+      ("workspace", "reserved"),
+      ("$workspace_index", "reserved"),
+      ("my-var", "Invalid variable name"),
+      ("$", "Invalid variable name"),
+    ] {
+      let config = yaml(&format!("variables:\n  '{}': a\n", name));
+      let err = config_variables(&config).unwrap_err().to_string();
+
+      assert!(err.contains(error), "{}: {}", name, err);
+    }
+
+    let config = yaml("variables:\n  mod: [alt]\n");
+    assert!(config_variables(&config).is_err());
+  }
+}
@@ -0,0 +1,138 @@
+use std::{
+  collections::HashMap,
+  fs,
+  path::PathBuf,
+  time::{Duration, SystemTime},
+};
+
+use tokio::{
+  sync::{mpsc, watch},
+  task, time,
+};
+use tracing::info;
+
+/// Interval at which the watched files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Time that the watched files need to stay unchanged before a change is
+/// reported. Editors often save a file in multiple writes.
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(500);
+
+/// Watches the config file and any files that it includes, and signals
+/// when they change. Directories can be watched as well, in which case
+/// added or removed files count as a change.
+///
+/// Files are polled rather than watched via OS notifications, since
+/// editors commonly save by replacing the file, which would need the
+/// watch to be re-registered.
+pub struct ConfigWatcher {
+  /// Receives a message once the watched files have changed and then
+  /// stayed unchanged for the debounce delay.
+  pub change_rx: mpsc::UnboundedReceiver<()>,
+  paths_tx: watch::Sender<Vec<PathBuf>>,
+  abort_handle: task::AbortHandle,
+}
+
+/// Last modified time of each watched file. `None` if the file couldn't
+/// be read (e.g. while it's being replaced).
+type FileStamps = HashMap<PathBuf, Option<SystemTime>>;
+
+impl ConfigWatcher {
+  /// Starts watching the given files. Nothing is watched if the list is
+  /// empty.
+  pub fn start(paths: Vec<PathBuf>) -> Self {
+    let (change_tx, change_rx) = mpsc::unbounded_channel();
+    let (paths_tx, paths_rx) = watch::channel(paths);
+
+    let task = task::spawn(Self::watch(paths_rx, change_tx));
+
+    Self {
+      change_rx,
+      paths_tx,
+      abort_handle: task.abort_handle(),
+    }
+  }
+
+  /// Updates the files to watch (e.g. after the config was reloaded with
+  /// different includes). Pass an empty list to stop watching.
+  pub fn update(&self, paths: Vec<PathBuf>) {
+    self.paths_tx.send_if_modified(|current_paths| {
+      let is_changed = *current_paths != paths;
+      *current_paths = paths;
+      is_changed
+    });
+  }
+
+  async fn watch(
+    mut paths_rx: watch::Receiver<Vec<PathBuf>>,
+    change_tx: mpsc::UnboundedSender<()>,
+  ) {
+    let mut stamps = Self::stamps(&paths_rx.borrow_and_update());
+
+    // Time of the most recent change that hasn't been reported yet.
+    let mut pending_change: Option<time::Instant> = None;
+
+    loop {
+      // Wait for files to watch if there are none.
+      if stamps.is_empty() {
+        if paths_rx.changed().await.is_err() {
+          return;
+        }
+
+        stamps = Self::stamps(&paths_rx.borrow_and_update());
+        continue;
+      }
+
+      tokio::select! {
+        _ = time::sleep(POLL_INTERVAL) => {},
+        res = paths_rx.changed() => {
+          if res.is_err() {
+            return;
+          }
+
+          // Files that are newly watched aren't treated as changed.
+          stamps = Self::stamps(&paths_rx.borrow_and_update());
+          continue;
+        }
+      }
+
+      let paths = stamps.keys().cloned().collect::<Vec<_>>();
+      let new_stamps = Self::stamps(&paths);
+
+      if new_stamps != stamps {
+        stamps = new_stamps;
+        pending_change = Some(time::Instant::now());
+        continue;
+      }
+
+      if pending_change
+        .is_some_and(|changed_at| changed_at.elapsed() >= DEBOUNCE_DELAY)
+      {
+        pending_change = None;
+        info!("Config file changed.");
+
+        if change_tx.send(()).is_err() {
+          return;
+        }
+      }
+    }
+  }
+
+  fn stamps(paths: &[PathBuf]) -> FileStamps {
+    paths
+      .iter()
+      .map(|path| {
+        let modified =
+          fs::metadata(path).and_then(|metadata| metadata.modified());
+
+        (path.clone(), modified.ok())
+      })
+      .collect()
+  }
+}
+
+impl Drop for ConfigWatcher {
+  fn drop(&mut self) {
+    self.abort_handle.abort();
+  }
+}
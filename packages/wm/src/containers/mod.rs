@@ -1,11 +1,10 @@
 pub mod commands;
 mod container;
-mod container_dto;
 mod root_container;
 mod split_container;
 pub mod traits;
 
 pub use container::*;
-pub use container_dto::*;
 pub use root_container::*;
 pub use split_container::*;
+pub use wm_common::{ContainerDto, RootContainerDto, SplitContainerDto};
@@ -5,8 +5,8 @@ use std::{
 };
 
 use anyhow::bail;
-use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use wm_common::RootContainerDto;
 
 use super::{
   traits::{CommonGetters, PositionGetters},
@@ -29,18 +29,6 @@ struct RootContainerInner {
   child_focus_order: VecDeque<Uuid>,
 }
 
-/// User-friendly representation of a root container.
-///
-/// Used for IPC and debug logging.
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct RootContainerDto {
-  id: Uuid,
-  parent_id: Option<Uuid>,
-  children: Vec<ContainerDto>,
-  child_focus_order: Vec<Uuid>,
-}
-
 impl Default for RootContainer {
   fn default() -> Self {
     let root = RootContainerInner {
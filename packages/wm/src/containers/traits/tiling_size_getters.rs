@@ -23,10 +23,16 @@ pub trait TilingSizeGetters: CommonGetters {
   fn set_gaps_config(&self, gaps_config: GapsConfig);
 
   /// Gets the horizontal and vertical gaps between windows in pixels.
+  ///
+  /// Uses the gaps of the parent workspace, since these can be
+  /// overridden per workspace.
   fn inner_gaps(&self) -> anyhow::Result<(i32, i32)> {
     let monitor = self.monitor().context("No monitor.")?;
     let monitor_rect = monitor.to_rect()?;
-    let gaps_config = self.gaps_config();
+    let gaps_config = match self.workspace() {
+      Some(workspace) => workspace.gaps_config(),
+      None => self.gaps_config().clone(),
+    };
 
     let scale_factor = match gaps_config.scale_with_dpi {
       true => monitor.native().scale_factor()?,
@@ -0,0 +1,558 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::{bail, Context};
+use serde_json::Value;
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  sync::{broadcast, mpsc},
+  time,
+};
+use tokio_tungstenite::tungstenite::Message;
+use wm_common::{
+  is_auth_token_match, quote_command_arg, ClientMessage,
+  ClientResponseMessage, ServerMessage,
+};
+use wm_ipc_client::IpcStream;
+
+use crate::ipc_server::IncomingMessageSender;
+
+/// Max size of the request line and headers of an HTTP request.
+const MAX_HEAD_SIZE: usize = 16 * 1024;
+
+/// Max size of the body of an HTTP request.
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Max time to wait for the request head after a client connects.
+pub const REQUEST_HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Interval at which comments are sent on event streams. Writing to the
+/// stream is the only way to notice that a client has disconnected.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Request line and headers of an incoming HTTP request.
+pub struct HttpRequestHead {
+  pub method: String,
+
+  /// Path without the query string (e.g. `/query/windows`).
+  pub path: String,
+
+  /// Decoded query string parameters, in the order they were given.
+  pub params: Vec<(String, String)>,
+
+  /// Headers with lowercase names.
+  headers: Vec<(String, String)>,
+
+  /// Length of the request head in bytes.
+  head_len: usize,
+}
+
+impl HttpRequestHead {
+  /// Reads the request head from the stream.
+  ///
+  /// Returns the head along with all bytes read so far, which include the
+  /// head itself and possibly the start of the body.
+  pub async fn read(
+    stream: &mut Box<dyn IpcStream>,
+  ) -> anyhow::Result<(Self, Vec<u8>)> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 4096];
+
+    loop {
+      let read_len = stream.read(&mut chunk).await?;
+
+      if read_len == 0 {
+        bail!("Connection closed before a request was received.");
+      }
+
+      buffer.extend_from_slice(&chunk[..read_len]);
+
+      let mut headers = [httparse::EMPTY_HEADER; 64];
+      let mut request = httparse::Request::new(&mut headers);
+
+      if let httparse::Status::Complete(head_len) =
+        request.parse(&buffer)?
+      {
+        let head = Self::from_parsed(&request, head_len);
+        return Ok((head, buffer));
+      }
+
+      if buffer.len() > MAX_HEAD_SIZE {
+        bail!("Request head exceeds {} bytes.", MAX_HEAD_SIZE);
+      }
+    }
+  }
+
+  fn from_parsed(request: &httparse::Request, head_len: usize) -> Self {
+    let target = request.path.unwrap_or("/");
+
+    let (path, query) = match target.split_once('?') {
+      Some((path, query)) => (path, query),
+      None => (target, ""),
+    };
+
+    let params = form_urlencoded::parse(query.as_bytes())
+      .map(|(key, value)| (key.into_owned(), value.into_owned()))
+      .collect();
+
+    let headers = request
+      .headers
+      .iter()
+      .map(|header| {
+        (
+          header.name.to_ascii_lowercase(),
+          String::from_utf8_lossy(header.value).into_owned(),
+        )
+      })
+      .collect();
+
+    Self {
+      method: request.method.unwrap_or_default().to_string(),
+      path: path.to_string(),
+      params,
+      headers,
+      head_len,
+    }
+  }
+
+  /// Gets the value of a header by its case-insensitive name.
+  pub fn header(&self, name: &str) -> Option<&str> {
+    self
+      .headers
+      .iter()
+      .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.as_str())
+  }
+
+  /// Whether the request is a websocket handshake.
+  pub fn is_websocket_upgrade(&self) -> bool {
+    self
+      .header("upgrade")
+      .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+  }
+}
+
+/// IPC message that an HTTP request maps onto.
+enum HttpRoute {
+  /// Query or WM command with a single response.
+  Request(String),
+  /// Event subscription that is streamed as server-sent events.
+  EventStream(String),
+}
+
+/// Error response with an HTTP status code.
+struct HttpError {
+  status: u16,
+  message: String,
+}
+
+impl HttpError {
+  fn new(status: u16, message: impl Into<String>) -> Self {
+    Self {
+      status,
+      message: message.into(),
+    }
+  }
+}
+
+/// Handles a plain HTTP request by forwarding it to the WM as an IPC
+/// message.
+///
+/// Responses have the same JSON shape as websocket responses.
+pub async fn handle_request(
+  head: HttpRequestHead,
+  buffered: Vec<u8>,
+  mut stream: Box<dyn IpcStream>,
+  message_tx: IncomingMessageSender,
+  auth_token: Option<Arc<String>>,
+  shutdown_rx: broadcast::Receiver<()>,
+) -> anyhow::Result<()> {
+  let description = format!("{} {}", head.method, head.path);
+
+  let route = match check_origin(&head)
+    .and_then(|_| authenticate(&head, auth_token.as_deref()))
+  {
+    Ok(_) => read_body(&head, buffered, &mut stream)
+      .await
+      .and_then(|body| to_route(&head, body)),
+    Err(err) => Err(err),
+  };
+
+  let route = match route {
+    Ok(route) => route,
+    Err(err) => {
+      let response = error_response(&description, &err.message)?;
+      return write_response(&mut stream, err.status, &response).await;
+    }
+  };
+
+  let (response_tx, mut response_rx) = mpsc::unbounded_channel();
+  let (disconnection_tx, _) = broadcast::channel(16);
+
+  let (message, is_event_stream) = match route {
+    HttpRoute::Request(message) => (message, false),
+    HttpRoute::EventStream(message) => (message, true),
+  };
+
+  message_tx.send((message, response_tx, disconnection_tx.clone()))?;
+
+  let response = response_rx
+    .recv()
+    .await
+    .context("No response received from WM.")?
+    .into_text()?;
+
+  let is_success = serde_json::from_str::<Value>(&response)?
+    .get("success")
+    .and_then(Value::as_bool)
+    .unwrap_or(false);
+
+  if !is_success || !is_event_stream {
+    let status = if is_success { 200 } else { 400 };
+    return write_response(&mut stream, status, &response).await;
+  }
+
+  // The subscription response is sent as the first event, so that
+  // clients get the subscription ID.
+  let stream_res =
+    write_event_stream(&mut stream, response, response_rx, shutdown_rx)
+      .await;
+
+  // Ends the event subscription.
+  let _ = disconnection_tx.send(());
+
+  stream_res
+}
+
+/// Rejects requests made by browsers, which always send an `Origin`
+/// header on cross-origin requests.
+///
+/// Otherwise, any website could run commands via a form submission to
+/// `POST /command`, since those aren't subject to CORS preflight checks.
+fn check_origin(head: &HttpRequestHead) -> Result<(), HttpError> {
+  match head.header("origin") {
+    None => Ok(()),
+    Some(_) => Err(HttpError::new(
+      403,
+      "Requests from browsers are not allowed.",
+    )),
+  }
+}
+
+/// Checks the `Authorization` header if the server requires auth.
+fn authenticate(
+  head: &HttpRequestHead,
+  auth_token: Option<&String>,
+) -> Result<(), HttpError> {
+  let Some(auth_token) = auth_token else {
+    return Ok(());
+  };
+
+  let token = head
+    .header("authorization")
+    .and_then(|header| header.strip_prefix("Bearer "));
+
+  match is_auth_token_match(auth_token, token) {
+    true => Ok(()),
+    false => Err(HttpError::new(401, "Invalid IPC auth token.")),
+  }
+}
+
+/// Reads the request body based on its `Content-Length` header.
+async fn read_body(
+  head: &HttpRequestHead,
+  buffered: Vec<u8>,
+  stream: &mut Box<dyn IpcStream>,
+) -> Result<String, HttpError> {
+  if head.header("transfer-encoding").is_some() {
+    return Err(HttpError::new(
+      411,
+      "Chunked request bodies are not supported.",
+    ));
+  }
+
+  let content_len = match head.header("content-length") {
+    Some(value) => value.trim().parse::<usize>().map_err(|_| {
+      HttpError::new(400, "Invalid Content-Length header.")
+    })?,
+    None => 0,
+  };
+
+  if content_len > MAX_BODY_SIZE {
+    return Err(HttpError::new(
+      413,
+      format!("Request body exceeds {} bytes.", MAX_BODY_SIZE),
+    ));
+  }
+
+  let mut body = buffered[head.head_len..].to_vec();
+  body.truncate(content_len);
+
+  if body.len() < content_len {
+    let mut rest = vec![0; content_len - body.len()];
+
+    stream
+      .read_exact(&mut rest)
+      .await
+      .map_err(|_| HttpError::new(400, "Incomplete request body."))?;
+
+    body.extend(rest);
+  }
+
+  String::from_utf8(body)
+    .map_err(|_| HttpError::new(400, "Request body must be UTF-8."))
+}
+
+/// Maps the request onto an IPC message.
+///
+/// Query string parameters are passed as flags (e.g.
+/// `/query/windows?process=chrome` becomes
+/// `query windows --process chrome`).
+fn to_route(
+  head: &HttpRequestHead,
+  body: String,
+) -> Result<HttpRoute, HttpError> {
+  let segments =
+    head.path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+  match (head.method.as_str(), segments.as_slice()) {
+    ("GET", ["query", query]) => {
+      if !is_valid_name(query, '-') {
+        return Err(HttpError::new(404, "Not found."));
+      }
+
+      Ok(HttpRoute::Request(format!(
+        "query {}{}",
+        query,
+        to_flags(&head.params)?
+      )))
+    }
+    ("POST", ["command"]) => {
+      // JSON bodies are forwarded as is, which allows command batches.
+      // Other messages (e.g. queries and subscriptions) have their own
+      // routes.
+      if body.trim_start().starts_with('{') {
+        let message = ClientMessage::parse(&body).map_err(|err| {
+          HttpError::new(400, format!("Invalid JSON body: {}", err))
+        })?;
+
+        let is_command = message.batch.is_some()
+          || message.command.split_whitespace().next() == Some("command");
+
+        if !is_command {
+          return Err(HttpError::new(
+            400,
+            "Body must be a command or a command batch.",
+          ));
+        }
+
+        return Ok(HttpRoute::Request(body));
+      }
+
+      if body.trim().is_empty() {
+        return Err(HttpError::new(400, "Missing command in body."));
+      }
+
+      Ok(HttpRoute::Request(format!(
+        "command{} {}",
+        to_flags(&head.params)?,
+        body.trim()
+      )))
+    }
+    ("GET", ["events"]) => {
+      let (types, filters): (Vec<_>, Vec<_>) = head
+        .params
+        .iter()
+        .cloned()
+        .partition(|(key, _)| key == "types");
+
+      let events = types
+        .iter()
+        .flat_map(|(_, value)| value.split(','))
+        .filter(|event| !event.is_empty())
+        .collect::<Vec<_>>();
+
+      if events.is_empty() {
+        return Err(HttpError::new(
+          400,
+          "Missing event types (e.g. `?types=focus_changed`).",
+        ));
+      }
+
+      if let Some(event) =
+        events.iter().find(|event| !is_valid_name(event, '_'))
+      {
+        return Err(HttpError::new(
+          400,
+          format!("Invalid event type '{}'.", event),
+        ));
+      }
+
+      Ok(HttpRoute::EventStream(format!(
+        "sub -e {}{}",
+        events.join(" "),
+        to_flags(&filters)?
+      )))
+    }
+    (_, ["query", _] | ["command"] | ["events"]) => {
+      Err(HttpError::new(405, "Method not allowed."))
+    }
+    _ => Err(HttpError::new(404, "Not found.")),
+  }
+}
+
+/// Converts query string parameters to CLI flags (e.g. ` --depth=1`).
+/// Parameters without a value become boolean flags.
+///
+/// Fails if a parameter name isn't a valid flag name.
+fn to_flags(params: &[(String, String)]) -> Result<String, HttpError> {
+  params
+    .iter()
+    .map(|(key, value)| {
+      if !is_valid_name(key, '-') {
+        return Err(HttpError::new(
+          400,
+          format!("Invalid query parameter '{}'.", key),
+        ));
+      }
+
+      Ok(match value.is_empty() {
+        true => format!(" --{}", key),
+        false => format!(" --{}={}", key, quote_command_arg(value)),
+      })
+    })
+    .collect()
+}
+
+/// Whether a name from the request only consists of lowercase letters,
+/// digits and the given separator, and starts with a letter (e.g.
+/// `process-regex`).
+///
+/// Names are inserted into the IPC message unquoted, so this ensures that
+/// they can't add other arguments.
+fn is_valid_name(name: &str, separator: char) -> bool {
+  let mut chars = name.chars();
+
+  chars.next().is_some_and(|char| char.is_ascii_lowercase())
+    && chars.all(|char| {
+      char.is_ascii_lowercase()
+        || char.is_ascii_digit()
+        || char == separator
+    })
+}
+
+/// Gets a serialized response message for an error that occurred before
+/// the request reached the WM.
+fn error_response(
+  description: &str,
+  error: &str,
+) -> anyhow::Result<String> {
+  let message = ServerMessage::ClientResponse(ClientResponseMessage {
+    client_message: description.to_string(),
+    client_message_id: None,
+    data: None,
+    error: Some(error.to_string()),
+    success: false,
+  });
+
+  Ok(serde_json::to_string(&message)?)
+}
+
+/// Writes a JSON response and closes the connection.
+async fn write_response(
+  stream: &mut Box<dyn IpcStream>,
+  status: u16,
+  body: &str,
+) -> anyhow::Result<()> {
+  let response = format!(
+    "HTTP/1.1 {} {}\r\n\
+    Content-Type: application/json\r\n\
+    Content-Length: {}\r\n\
+    Connection: close\r\n\r\n{}",
+    status,
+    reason_phrase(status),
+    body.len(),
+    body
+  );
+
+  stream.write_all(response.as_bytes()).await?;
+  stream.shutdown().await?;
+
+  Ok(())
+}
+
+/// Streams subscription messages as server-sent events until either the
+/// client or the WM closes the connection.
+///
+/// On shutdown, a final `shutdown` event is sent so that clients can
+/// tell it apart from a dropped connection.
+async fn write_event_stream(
+  stream: &mut Box<dyn IpcStream>,
+  first_message: String,
+  mut message_rx: mpsc::UnboundedReceiver<Message>,
+  mut shutdown_rx: broadcast::Receiver<()>,
+) -> anyhow::Result<()> {
+  let head = "HTTP/1.1 200 OK\r\n\
+    Content-Type: text/event-stream\r\n\
+    Cache-Control: no-cache\r\n\
+    Connection: keep-alive\r\n\r\n";
+
+  stream.write_all(head.as_bytes()).await?;
+  write_event(stream, &first_message).await?;
+
+  let mut keepalive = time::interval(KEEPALIVE_INTERVAL);
+
+  loop {
+    tokio::select! {
+      message = message_rx.recv() => {
+        let Some(message) = message else {
+          break;
+        };
+
+        write_event(stream, message.to_text()?).await?;
+      }
+      _ = shutdown_rx.recv() => {
+        stream
+          .write_all(b"event: shutdown\ndata: WM is shutting down.\n\n")
+          .await?;
+        stream.shutdown().await?;
+        break;
+      }
+      _ = keepalive.tick() => {
+        stream.write_all(b": keepalive\n\n").await?;
+        stream.flush().await?;
+      }
+    }
+  }
+
+  Ok(())
+}
+
+async fn write_event(
+  stream: &mut Box<dyn IpcStream>,
+  data: &str,
+) -> anyhow::Result<()> {
+  stream
+    .write_all(format!("data: {}\n\n", data).as_bytes())
+    .await?;
+  stream.flush().await?;
+
+  Ok(())
+}
+
+/// Gets the reason phrase for a status code. Falls back to a generic
+/// phrase for the class of the status code.
+fn reason_phrase(status: u16) -> &'static str {
+  match status {
+    200 => "OK",
+    400 => "Bad Request",
+    401 => "Unauthorized",
+    403 => "Forbidden",
+    404 => "Not Found",
+    405 => "Method Not Allowed",
+    411 => "Length Required",
+    413 => "Payload Too Large",
+    200..=299 => "Success",
+    400..=499 => "Client Error",
+    _ => "Server Error",
+  }
+}
@@ -1,157 +1,309 @@
-use std::{iter, net::SocketAddr};
+use std::{fs, iter, path::PathBuf, sync::Arc, time::Duration};
 
 use anyhow::{bail, Context};
-use clap::Parser;
+use clap::{CommandFactory, Parser, ValueEnum};
 use futures_util::{SinkExt, StreamExt};
-use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use tokio::{
-  net::{TcpListener, TcpStream},
-  sync::{broadcast, mpsc},
+  sync::{
+    broadcast::{
+      self,
+      error::{RecvError, TryRecvError},
+    },
+    mpsc,
+  },
   task,
+  time::{self, Instant},
+};
+use tokio_tungstenite::{
+  accept_hdr_async,
+  tungstenite::{
+    handshake::server::{ErrorResponse, Request, Response},
+    http::{header::AUTHORIZATION, StatusCode},
+    protocol::{frame::coding::CloseCode, CloseFrame},
+    Message,
+  },
 };
-use tokio_tungstenite::{accept_async, tungstenite::Message};
 use tracing::{info, warn};
 use uuid::Uuid;
+use wm_common::{
+  is_auth_token_match, offending_arg, parse_command_chain,
+  AppMetadataData, BindingModesData, CapabilitiesData, ChainOperator,
+  ChainedCommand, ClientMessage, ClientResponseData,
+  ClientResponseMessage, CommandArg, CommandBatch, CommandBatchData,
+  CommandData, CommandResult, CommandSegment, CommandSyntaxError,
+  ContainerData, EventSubscribeData, EventSubscriptionMessage,
+  FocusedData, IpcFeature, MonitorsData, ServerMessage,
+  TilingDirectionData, TreeData, WindowsData, WorkspacesData,
+  IPC_PROTOCOL_VERSION,
+};
+use wm_ipc_client::{IpcListener, IpcStream, PrefixedStream};
 
 use crate::{
-  app_command::{AppCommand, QueryCommand, SubscribableEvent},
-  common::TilingDirection,
+  app_command::{
+    AppCommand, EventFilterArgs, InvokeCommand, QueryCommand,
+    QueryFilterArgs, SubscribableEvent,
+  },
+  common::platform::Platform,
   containers::{
     traits::{CommonGetters, TilingDirectionGetters},
     ContainerDto,
   },
-  user_config::{BindingModeConfig, UserConfig},
+  ipc_http::{self, HttpRequestHead},
+  user_config::{IpcConfig, UserConfig},
   wm::WindowManager,
-  wm_event::WmEvent,
+  wm_event::{WmEvent, WmEventContext},
 };
 
-pub const DEFAULT_IPC_PORT: u32 = 6123;
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(tag = "messageType", rename_all = "snake_case")]
-pub enum ServerMessage {
-  ClientResponse(ClientResponseMessage),
-  EventSubscription(EventSubscriptionMessage),
-}
+/// Sender for incoming client messages, along with the channels to
+/// respond on and to signal disconnection.
+pub type IncomingMessageSender = mpsc::UnboundedSender<(
+  String,
+  mpsc::UnboundedSender<Message>,
+  broadcast::Sender<()>,
+)>;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ClientResponseMessage {
-  pub client_message: String,
-  pub data: Option<ClientResponseData>,
-  pub error: Option<String>,
-  pub success: bool,
-}
+/// Max time to wait for pending messages to be flushed to clients when
+/// shutting down.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(untagged)]
-pub enum ClientResponseData {
-  AppMetadata(AppMetadataData),
-  BindingModes(BindingModesData),
-  Command(CommandData),
-  EventSubscribe(EventSubscribeData),
-  EventUnsubscribe,
-  Focused(FocusedData),
-  Monitors(MonitorsData),
-  TilingDirection(TilingDirectionData),
-  Windows(WindowsData),
-  Workspaces(WorkspacesData),
+/// State that is shared by all connections on a listener.
+#[derive(Clone)]
+struct ConnectionContext {
+  message_tx: IncomingMessageSender,
+  auth_token: Option<Arc<String>>,
+  /// Whether plain HTTP requests are answered on the listener.
+  is_http_enabled: bool,
+  /// Interval at which clients are pinged.
+  ping_interval: Duration,
+  /// Time without any incoming messages after which a connection is
+  /// closed.
+  idle_timeout: Option<Duration>,
+  shutdown_tx: broadcast::Sender<()>,
+  /// Held by each connection, so that shutting down can wait until all
+  /// connections have been closed.
+  _shutdown_complete_tx: mpsc::Sender<()>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct AppMetadataData {
-  pub version: String,
+/// WM event along with the metadata needed to filter subscriptions.
+#[derive(Clone, Debug)]
+struct EmittedEvent {
+  event: WmEvent,
+  event_type: SubscribableEvent,
+  sequence: u64,
+  /// Details of the event's container, captured when it was emitted.
+  context: WmEventContext,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct BindingModesData {
-  pub binding_modes: Vec<BindingModeConfig>,
-}
+impl EmittedEvent {
+  /// Whether the event should be forwarded to a subscription with the
+  /// given event types and filter.
+  fn is_match(
+    &self,
+    events: &[SubscribableEvent],
+    filter: &EventFilterArgs,
+  ) -> bool {
+    if !events.contains(&self.event_type)
+      && !events.contains(&SubscribableEvent::All)
+    {
+      return false;
+    }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CommandData {
-  pub subject_container_id: Uuid,
-}
+    // Events that aren't tied to a container are only forwarded if no
+    // filters are set.
+    let Some(container_id) = self.event.container_id() else {
+      return filter.container_id.is_none()
+        && filter.workspace.is_none()
+        && filter.monitor.is_none()
+        && !filter.window.is_set();
+    };
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct EventSubscribeData {
-  pub subscription_id: Uuid,
-}
+    if filter
+      .container_id
+      .is_some_and(|filter_id| filter_id != container_id)
+    {
+      return false;
+    }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct FocusedData {
-  pub focused: ContainerDto,
-}
+    if filter.workspace.as_ref().is_some_and(|name| {
+      self.context.workspace_name.as_ref() != Some(name)
+    }) {
+      return false;
+    }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct MonitorsData {
-  pub monitors: Vec<ContainerDto>,
-}
+    if filter
+      .monitor
+      .is_some_and(|index| self.context.monitor_index != Some(index))
+    {
+      return false;
+    }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct TilingDirectionData {
-  pub tiling_direction: TilingDirection,
-  pub direction_container: ContainerDto,
-}
+    if !filter.window.is_set() {
+      return true;
+    }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct WindowsData {
-  pub windows: Vec<ContainerDto>,
-}
+    let match_config = filter.window.to_match_config();
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct WorkspacesData {
-  pub workspaces: Vec<ContainerDto>,
-}
+    // Removed containers are only present in the event's context.
+    let container = self
+      .event
+      .container()
+      .or(self.context.removed_container.as_ref());
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct EventSubscriptionMessage {
-  pub data: Option<WmEvent>,
-  pub error: Option<String>,
-  pub subscription_id: Uuid,
-  pub success: bool,
+    container.is_some_and(|container| {
+      container
+        .self_and_descendant_windows()
+        .iter()
+        .any(|window| {
+          match_config.is_match(
+            &window.process_name,
+            &window.class_name,
+            &window.title,
+          )
+        })
+    })
+  }
 }
 
 pub struct IpcServer {
-  abort_handle: task::AbortHandle,
+  /// Abort handles for the tasks accepting connections on each listener.
+  abort_handles: Vec<task::AbortHandle>,
+  /// Path to the auth token file. Only present if auth is required.
+  auth_token_path: Option<PathBuf>,
   pub message_rx: mpsc::UnboundedReceiver<(
     String,
     mpsc::UnboundedSender<Message>,
     broadcast::Sender<()>,
   )>,
-  _event_rx: broadcast::Receiver<(SubscribableEvent, WmEvent)>,
-  event_tx: broadcast::Sender<(SubscribableEvent, WmEvent)>,
+  _event_rx: broadcast::Receiver<EmittedEvent>,
+  event_tx: broadcast::Sender<EmittedEvent>,
+  /// Sequence number of the last emitted WM event.
+  event_sequence: u64,
   _unsubscribe_rx: broadcast::Receiver<Uuid>,
   unsubscribe_tx: broadcast::Sender<Uuid>,
+  /// Signals connections and subscriptions to flush pending messages
+  /// and close.
+  shutdown_tx: broadcast::Sender<()>,
+  shutdown_complete_tx: Option<mpsc::Sender<()>>,
+  shutdown_complete_rx: mpsc::Receiver<()>,
 }
 
 impl IpcServer {
-  pub async fn start() -> anyhow::Result<Self> {
+  pub async fn start(ipc_config: &IpcConfig) -> anyhow::Result<Self> {
     let (message_tx, message_rx) = mpsc::unbounded_channel();
-    let (event_tx, _event_rx) = broadcast::channel(16);
+    let (event_tx, _event_rx) =
+      broadcast::channel(ipc_config.event_buffer_size.max(1));
     let (unsubscribe_tx, _unsubscribe_rx) = broadcast::channel(16);
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (shutdown_complete_tx, shutdown_complete_rx) = mpsc::channel(1);
 
-    let server_addr = format!("127.0.0.1:{}", DEFAULT_IPC_PORT);
-    let server = TcpListener::bind(server_addr.clone()).await?;
-    info!("IPC server started on: '{}'.", server_addr);
+    let listener = IpcListener::bind(ipc_config).await?;
+    info!("IPC server started on: '{}'.", listener.local_addr());
+
+    let http_listener = IpcListener::bind_http(ipc_config).await?;
+
+    if let Some(http_listener) = &http_listener {
+      info!("HTTP server started on: '{}'.", http_listener.local_addr());
+    }
 
+    let (auth_token, auth_token_path) = match ipc_config.require_auth {
+      true => {
+        let (token, token_path) = Self::write_auth_token(ipc_config)?;
+        (Some(Arc::new(token)), Some(token_path))
+      }
+      false => (None, None),
+    };
+
+    let context = ConnectionContext {
+      message_tx,
+      auth_token,
+      // HTTP is served on the websocket listener, unless a separate HTTP
+      // port is configured.
+      is_http_enabled: ipc_config.http.enabled && http_listener.is_none(),
+      ping_interval: Duration::from_secs(ipc_config.ping_interval.max(1)),
+      idle_timeout: match ipc_config.idle_timeout {
+        0 => None,
+        timeout => Some(Duration::from_secs(timeout)),
+      },
+      shutdown_tx: shutdown_tx.clone(),
+      _shutdown_complete_tx: shutdown_complete_tx.clone(),
+    };
+
+    let mut abort_handles =
+      vec![Self::spawn_accept_loop(listener, context.clone())];
+
+    if let Some(http_listener) = http_listener {
+      abort_handles.push(Self::spawn_accept_loop(
+        http_listener,
+        ConnectionContext {
+          is_http_enabled: true,
+          ..context
+        },
+      ));
+    }
+
+    Ok(Self {
+      abort_handles,
+      auth_token_path,
+      _event_rx,
+      event_tx,
+      event_sequence: 0,
+      message_rx,
+      unsubscribe_tx,
+      _unsubscribe_rx,
+      shutdown_tx,
+      shutdown_complete_tx: Some(shutdown_complete_tx),
+      shutdown_complete_rx,
+    })
+  }
+
+  /// Generates a random auth token and writes it to a file that only
+  /// the current user can read.
+  ///
+  /// Returns the token and the path to the token file.
+  fn write_auth_token(
+    ipc_config: &IpcConfig,
+  ) -> anyhow::Result<(String, PathBuf)> {
+    let token = Uuid::new_v4().simple().to_string();
+    let token_path = ipc_config.auth_token_path()?;
+
+    if let Some(parent_dir) = token_path.parent() {
+      fs::create_dir_all(parent_dir)?;
+    }
+
+    Platform::write_private_file(&token_path, &token).with_context(
+      || format!("Unable to write IPC token to {}.", token_path.display()),
+    )?;
+
+    info!("IPC auth token written to: '{}'.", token_path.display());
+
+    Ok((token, token_path))
+  }
+
+  /// Spawns a task that accepts connections on the given listener.
+  fn spawn_accept_loop(
+    mut listener: IpcListener,
+    context: ConnectionContext,
+  ) -> task::AbortHandle {
     let task = task::spawn(async move {
-      while let Ok((stream, addr)) = server.accept().await {
-        let message_tx = message_tx.clone();
+      loop {
+        let (stream, addr) = match listener.accept().await {
+          Ok(connection) => connection,
+          Err(err) => {
+            // Keep accepting connections, since the error might only
+            // affect a single connection (e.g. if the client disconnected
+            // before being accepted).
+            warn!("Failed to accept IPC connection: {}", err);
+            time::sleep(Duration::from_millis(100)).await;
+            continue;
+          }
+        };
+
+        let context = context.clone();
 
         task::spawn(async move {
           if let Err(err) =
-            Self::handle_connection(stream, addr, message_tx).await
+            Self::handle_connection(stream, addr, context).await
           {
             warn!("Error handling connection: {}", err);
           }
@@ -159,34 +311,101 @@ impl IpcServer {
       }
     });
 
-    Ok(Self {
-      abort_handle: task.abort_handle(),
-      _event_rx,
-      event_tx,
-      message_rx,
-      unsubscribe_tx,
-      _unsubscribe_rx,
-    })
+    task.abort_handle()
   }
 
   async fn handle_connection(
-    stream: TcpStream,
-    addr: SocketAddr,
-    message_tx: mpsc::UnboundedSender<(
-      String,
-      mpsc::UnboundedSender<Message>,
-      broadcast::Sender<()>,
-    )>,
+    mut stream: Box<dyn IpcStream>,
+    addr: String,
+    context: ConnectionContext,
   ) -> anyhow::Result<()> {
+    let ConnectionContext {
+      message_tx,
+      auth_token,
+      ..
+    } = context.clone();
+
     info!("Incoming IPC connection from: {}.", addr);
 
-    let ws_stream = accept_async(stream)
+    // Subscribe before reading from the stream, so that a shutdown isn't
+    // missed while waiting on the client.
+    let mut shutdown_rx = context.shutdown_tx.subscribe();
+
+    if context.is_http_enabled {
+      // Read the request head up-front to tell websocket handshakes apart
+      // from plain HTTP requests.
+      let (request_head, buffered) = time::timeout(
+        ipc_http::REQUEST_HEAD_TIMEOUT,
+        HttpRequestHead::read(&mut stream),
+      )
       .await
-      .context("Error during websocket handshake.")?;
+      .context("Timed out reading request.")?
+      .context("Failed to read request.")?;
+
+      if !request_head.is_websocket_upgrade() {
+        return ipc_http::handle_request(
+          request_head,
+          buffered,
+          stream,
+          message_tx,
+          auth_token,
+          shutdown_rx,
+        )
+        .await;
+      }
+
+      // The websocket handshake needs to re-read the request head.
+      stream = Box::new(PrefixedStream::new(buffered, stream));
+    }
+
+    // Connections are authenticated either via an `Authorization` header
+    // in the websocket handshake or via a token in the first message.
+    let mut is_authenticated = auth_token.is_none();
+
+    let ws_stream = accept_hdr_async(
+      stream,
+      |req: &Request, res: Response| -> Result<Response, ErrorResponse> {
+        let Some(auth_token) = &auth_token else {
+          return Ok(res);
+        };
+
+        match req.headers().get(AUTHORIZATION) {
+          None => Ok(res),
+          Some(header) => {
+            let token = header
+              .to_str()
+              .ok()
+              .and_then(|header| header.strip_prefix("Bearer "));
+
+            if is_auth_token_match(auth_token, token) {
+              is_authenticated = true;
+              return Ok(res);
+            }
+
+            let mut err_res = ErrorResponse::new(Some(
+              "Invalid IPC auth token.".to_string(),
+            ));
+            *err_res.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(err_res)
+          }
+        }
+      },
+    )
+    .await
+    .context("Error during websocket handshake.")?;
 
     let (mut outgoing, mut incoming) = ws_stream.split();
     let (response_tx, mut response_rx) = mpsc::unbounded_channel();
     let (disconnection_tx, _) = broadcast::channel(16);
+    let mut is_shutting_down = false;
+
+    // Clients are pinged periodically, and the connection is closed if
+    // nothing is received from the client within the idle timeout.
+    let mut ping_interval = time::interval_at(
+      Instant::now() + context.ping_interval,
+      context.ping_interval,
+    );
+    let mut last_activity = Instant::now();
 
     loop {
       tokio::select! {
@@ -196,11 +415,68 @@ impl IpcServer {
             break;
           }
         }
+        _ = shutdown_rx.recv() => {
+          is_shutting_down = true;
+          break;
+        }
+        _ = ping_interval.tick() => {
+          if context
+            .idle_timeout
+            .is_some_and(|timeout| last_activity.elapsed() > timeout)
+          {
+            info!("Closing idle IPC connection from: {}.", addr);
+
+            let close_frame = CloseFrame {
+              code: CloseCode::Normal,
+              reason: "Connection was idle for too long.".into(),
+            };
+
+            let _ = outgoing.send(Message::Close(Some(close_frame))).await;
+            break;
+          }
+
+          if let Err(err) = outgoing.send(Message::Ping(Vec::new())).await {
+            warn!("Error sending ping: {}", err);
+            break;
+          }
+        }
         message = incoming.next() => {
           if let Some(Ok(message)) = message {
+            last_activity = Instant::now();
+
             if message.is_text() || message.is_binary() {
+              let message = message.to_text()?.to_owned();
+
+              if !is_authenticated {
+                let client_message = ClientMessage::parse(&message)
+                  .unwrap_or_else(|_| ClientMessage::from_command(&message));
+
+                let is_token_match =
+                  auth_token.as_deref().is_some_and(|auth_token| {
+                    is_auth_token_match(
+                      auth_token,
+                      client_message.token.as_deref(),
+                    )
+                  });
+
+                if !is_token_match {
+                  warn!("Rejected unauthenticated IPC client: {}.", addr);
+
+                  let response = Self::to_client_response_msg(
+                    client_message,
+                    Err(anyhow::anyhow!("Invalid IPC auth token.")),
+                  )?;
+
+                  outgoing.send(response).await?;
+                  outgoing.send(Message::Close(None)).await?;
+                  break;
+                }
+
+                is_authenticated = true;
+              }
+
               message_tx.send((
-                message.to_text()?.to_owned(),
+                message,
                 response_tx.clone(),
                 disconnection_tx.clone(),
               ))?;
@@ -213,6 +489,25 @@ impl IpcServer {
       }
     }
 
+    if is_shutting_down {
+      // Flush pending responses and events. The channel closes once all
+      // of the connection's subscriptions have flushed their events.
+      drop(response_tx);
+
+      while let Ok(Some(response)) =
+        time::timeout(SHUTDOWN_TIMEOUT, response_rx.recv()).await
+      {
+        outgoing.send(response).await?;
+      }
+
+      let close_frame = CloseFrame {
+        code: CloseCode::Away,
+        reason: "WM is shutting down.".into(),
+      };
+
+      outgoing.send(Message::Close(Some(close_frame))).await?;
+    }
+
     info!("IPC disconnection from: {}.", addr);
     disconnection_tx.send(())?;
 
@@ -227,30 +522,96 @@ impl IpcServer {
     wm: &mut WindowManager,
     config: &mut UserConfig,
   ) -> anyhow::Result<()> {
-    let app_command = AppCommand::try_parse_from(
-      iter::once("").chain(message.split_whitespace()),
-    );
+    let client_message = match ClientMessage::parse(&message) {
+      Ok(client_message) => client_message,
+      Err(err) => {
+        // Echo back the ID if the message is valid JSON, so that the
+        // client can match the error to its request.
+        let id = serde_json::from_str::<Value>(&message)
+          .ok()
+          .and_then(|value| value.get("id").cloned())
+          .and_then(|id| serde_json::from_value(id).ok());
+
+        response_tx.send(Self::to_client_response_msg(
+          ClientMessage {
+            id,
+            ..ClientMessage::from_command(&message)
+          },
+          Err(anyhow::anyhow!("Invalid client message: {}", err)),
+        )?)?;
+
+        return Ok(());
+      }
+    };
 
-    let response_data =
-      app_command
-        .map_err(anyhow::Error::msg)
-        .and_then(|app_command| {
-          self.handle_app_command(
-            app_command,
-            response_tx.clone(),
-            disconnection_tx,
+    let response_data = match &client_message.batch {
+      Some(batch) => Self::handle_command_batch(batch, wm, config),
+      None => parse_command_chain(&client_message.command)
+        .map_err(anyhow::Error::from)
+        .and_then(|segments| match segments.as_slice() {
+          [segment] => {
+            Self::parse_app_command(&client_message.command, &segment.args)
+              .and_then(|app_command| {
+                self.handle_app_command(
+                  app_command,
+                  response_tx.clone(),
+                  disconnection_tx,
+                  wm,
+                  config,
+                )
+              })
+          }
+          _ => Self::handle_command_chain(
+            &client_message.command,
+            &segments,
             wm,
             config,
-          )
-        });
+          ),
+        }),
+    };
 
     // Respond to the client with the result of the command.
-    response_tx
-      .send(Self::to_client_response_msg(message, response_data)?)?;
+    response_tx.send(Self::to_client_response_msg(
+      client_message,
+      response_data,
+    )?)?;
 
     Ok(())
   }
 
+  /// Gets the IPC protocol version and the supported queries, events and
+  /// features.
+  fn capabilities() -> CapabilitiesData {
+    let queries = AppCommand::command()
+      .find_subcommand("query")
+      .map(|query_command| {
+        query_command
+          .get_subcommands()
+          .map(|subcommand| subcommand.get_name().to_string())
+          .collect()
+      })
+      .unwrap_or_default();
+
+    let events = SubscribableEvent::value_variants()
+      .iter()
+      .filter_map(|event| event.to_possible_value())
+      .map(|value| value.get_name().to_string())
+      .collect();
+
+    let features = IpcFeature::ALL
+      .iter()
+      .map(|feature| feature.as_str().to_string())
+      .collect();
+
+    CapabilitiesData {
+      version: env!("VERSION_NUMBER").to_string(),
+      protocol_version: IPC_PROTOCOL_VERSION,
+      queries,
+      events,
+      features,
+    }
+  }
+
   fn handle_app_command(
     &self,
     app_command: AppCommand,
@@ -260,36 +621,30 @@ impl IpcServer {
     config: &mut UserConfig,
   ) -> anyhow::Result<ClientResponseData> {
     let response_data = match app_command {
-      AppCommand::Query { command } => match command {
-        QueryCommand::Windows => {
-          ClientResponseData::Windows(WindowsData {
-            windows: wm
-              .state
-              .windows()
-              .into_iter()
-              .map(|window| window.to_dto())
-              .try_collect()?,
-          })
+      AppCommand::Query { command, .. } => match command {
+        QueryCommand::Windows { filter } => {
+          let data = ClientResponseData::Windows(WindowsData {
+            windows: Self::filtered_dtos(wm.state.windows(), &filter)?,
+          });
+
+          Self::project_fields(data, &filter.fields)?
         }
-        QueryCommand::Workspaces => {
-          ClientResponseData::Workspaces(WorkspacesData {
-            workspaces: wm
-              .state
-              .workspaces()
-              .into_iter()
-              .map(|workspace| workspace.to_dto())
-              .try_collect()?,
-          })
+        QueryCommand::Workspaces { filter } => {
+          let data = ClientResponseData::Workspaces(WorkspacesData {
+            workspaces: Self::filtered_dtos(
+              wm.state.workspaces(),
+              &filter,
+            )?,
+          });
+
+          Self::project_fields(data, &filter.fields)?
         }
-        QueryCommand::Monitors => {
-          ClientResponseData::Monitors(MonitorsData {
-            monitors: wm
-              .state
-              .monitors()
-              .into_iter()
-              .map(|monitor| monitor.to_dto())
-              .try_collect()?,
-          })
+        QueryCommand::Monitors { filter } => {
+          let data = ClientResponseData::Monitors(MonitorsData {
+            monitors: Self::filtered_dtos(wm.state.monitors(), &filter)?,
+          });
+
+          Self::project_fields(data, &filter.fields)?
         }
         QueryCommand::BindingModes => {
           ClientResponseData::BindingModes(BindingModesData {
@@ -309,8 +664,12 @@ impl IpcServer {
         QueryCommand::AppMetadata => {
           ClientResponseData::AppMetadata(AppMetadataData {
             version: env!("VERSION_NUMBER").to_string(),
+            protocol_version: Some(IPC_PROTOCOL_VERSION),
           })
         }
+        QueryCommand::Capabilities => {
+          ClientResponseData::Capabilities(Self::capabilities())
+        }
         QueryCommand::TilingDirection => {
           let direction_container = wm
             .state
@@ -323,13 +682,38 @@ impl IpcServer {
             tiling_direction: direction_container.tiling_direction(),
           })
         }
+        QueryCommand::Tree { depth } => {
+          let mut root = wm.state.root_container.to_dto()?;
+
+          if let Some(depth) = depth {
+            root.truncate_depth(depth);
+          }
+
+          ClientResponseData::Tree(TreeData { root })
+        }
+        QueryCommand::Container { id, depth } => {
+          let mut container = wm
+            .state
+            .container_by_id(id)
+            .with_context(|| {
+              format!("No container found with the given ID '{}'.", id)
+            })?
+            .to_dto()?;
+
+          if let Some(depth) = depth {
+            container.truncate_depth(depth);
+          }
+
+          ClientResponseData::Container(ContainerData { container })
+        }
       },
       AppCommand::Command {
         subject_container_id,
         command,
+        ..
       } => {
         let subject_container_id = wm.process_commands(
-          vec![command],
+          vec![command.into()],
           subject_container_id,
           config,
         )?;
@@ -338,7 +722,9 @@ impl IpcServer {
           subject_container_id,
         })
       }
-      AppCommand::Sub { events } => {
+      AppCommand::Sub { events, filter, .. } => {
+        filter.window.validate()?;
+
         let subscription_id = Uuid::new_v4();
         info!("New event subscription {}: {:?}", subscription_id, events);
 
@@ -346,6 +732,7 @@ impl IpcServer {
         let mut event_rx = self.event_tx.subscribe();
         let mut unsubscribe_rx = self.unsubscribe_tx.subscribe();
         let mut disconnection_rx = disconnection_tx.subscribe();
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
 
         task::spawn(async move {
           loop {
@@ -353,26 +740,78 @@ impl IpcServer {
               Ok(_) = disconnection_rx.recv() => {
                 break;
               }
+              Ok(_) = shutdown_rx.recv() => {
+                // Flush events that were emitted before shutting down
+                // (e.g. `ApplicationExiting`).
+                loop {
+                  let res = match event_rx.try_recv() {
+                    Ok(emitted) => {
+                      if !emitted.is_match(&events, &filter) {
+                        continue;
+                      }
+
+                      Self::to_event_subscription_msg(
+                        subscription_id,
+                        emitted.event,
+                        emitted.sequence,
+                      )
+                    }
+                    Err(TryRecvError::Lagged(lagged_count)) => {
+                      Self::to_event_lagged_msg(
+                        subscription_id,
+                        lagged_count,
+                      )
+                    }
+                    Err(_) => break,
+                  };
+
+                  if res.and_then(|msg| Ok(response_tx.send(msg)?)).is_err()
+                  {
+                    break;
+                  }
+                }
+
+                break;
+              }
               Ok(id) = unsubscribe_rx.recv() => {
                 if id == subscription_id {
                   break;
                 }
               }
-              Ok((event_type, event)) = event_rx.recv() => {
-                // Check whether the event is one of the subscribed events.
-                if events.contains(&event_type)
-                  || events.contains(&SubscribableEvent::All)
-                {
-                  let res = Self::to_event_subscription_msg(
-                    subscription_id,
-                    event,
-                  )
-                  .map(|event_msg| response_tx.send(event_msg));
-
-                  if let Err(err) = res {
-                    warn!("Error emitting WM event: {}", err);
-                    break;
+              event = event_rx.recv() => {
+                let res = match event {
+                  Ok(emitted) => {
+                    // Check whether the event is one of the subscribed
+                    // events and passes the subscription's filter.
+                    if !emitted.is_match(&events, &filter) {
+                      continue;
+                    }
+
+                    Self::to_event_subscription_msg(
+                      subscription_id,
+                      emitted.event,
+                      emitted.sequence,
+                    )
                   }
+                  Err(RecvError::Lagged(lagged_count)) => {
+                    warn!(
+                      "Event subscription {} lagged by {} events.",
+                      subscription_id, lagged_count
+                    );
+
+                    Self::to_event_lagged_msg(
+                      subscription_id,
+                      lagged_count,
+                    )
+                  }
+                  Err(RecvError::Closed) => break,
+                };
+
+                if let Err(err) =
+                  res.and_then(|msg| Ok(response_tx.send(msg)?))
+                {
+                  warn!("Error emitting WM event: {}", err);
+                  break;
                 }
               }
             }
@@ -383,7 +822,9 @@ impl IpcServer {
           subscription_id,
         })
       }
-      AppCommand::Unsub { subscription_id } => {
+      AppCommand::Unsub {
+        subscription_id, ..
+      } => {
         self
           .unsubscribe_tx
           .send(subscription_id)
@@ -397,15 +838,209 @@ impl IpcServer {
     Ok(response_data)
   }
 
+  /// Converts the containers that match the given filter to DTOs.
+  fn filtered_dtos(
+    containers: Vec<impl CommonGetters>,
+    filter: &QueryFilterArgs,
+  ) -> anyhow::Result<Vec<ContainerDto>> {
+    filter.validate()?;
+
+    let mut dtos = Vec::new();
+
+    for container in containers {
+      if filter.is_match(&container)? {
+        dtos.push(container.to_dto()?);
+      }
+    }
+
+    Ok(dtos)
+  }
+
+  /// Removes all keys except the given fields from each container in the
+  /// response data.
+  ///
+  /// Returns the data unchanged if no fields are given.
+  fn project_fields(
+    data: ClientResponseData,
+    fields: &[String],
+  ) -> anyhow::Result<ClientResponseData> {
+    if fields.is_empty() {
+      return Ok(data);
+    }
+
+    let mut value = serde_json::to_value(data)?;
+
+    let containers = value
+      .as_object_mut()
+      .into_iter()
+      .flat_map(|data| data.values_mut())
+      .filter_map(Value::as_array_mut)
+      .flatten()
+      .filter_map(Value::as_object_mut);
+
+    for container in containers {
+      container.retain(|key, _| fields.contains(key));
+    }
+
+    Ok(ClientResponseData::Projected(value))
+  }
+
+  /// Runs a batch of WM commands with a single redraw at the end.
+  ///
+  /// The batch is rejected as a whole if any of the commands are invalid.
+  /// Otherwise, it stops at the first command that fails, without
+  /// rolling back the commands before it. The result of each command is
+  /// included in the response.
+  fn handle_command_batch(
+    batch: &CommandBatch,
+    wm: &mut WindowManager,
+    config: &mut UserConfig,
+  ) -> anyhow::Result<ClientResponseData> {
+    let commands = batch
+      .commands
+      .iter()
+      .enumerate()
+      .map(|(index, command)| {
+        command.parse::<InvokeCommand>().with_context(|| {
+          format!("Invalid command at index {}: '{}'.", index, command)
+        })
+      })
+      .try_collect::<Vec<_>>()?;
+
+    // Chain the commands with `&&`, such that commands after a failing
+    // command are not run and are marked as skipped.
+    let commands = commands
+      .into_iter()
+      .enumerate()
+      .map(|(index, command)| ChainedCommand {
+        command,
+        operator: (index > 0).then_some(ChainOperator::And),
+      })
+      .collect();
+
+    let (results, subject_container_id) = wm.process_command_batch(
+      commands,
+      batch.subject_container_id,
+      config,
+    )?;
+
+    let results = Self::command_results(batch.commands.clone(), results);
+
+    Ok(ClientResponseData::CommandBatch(CommandBatchData {
+      results,
+      subject_container_id,
+    }))
+  }
+
+  /// Parses an `AppCommand` from arguments that were split from the
+  /// given message. Parse errors point to the offending argument.
+  fn parse_app_command(
+    message: &str,
+    args: &[CommandArg],
+  ) -> anyhow::Result<AppCommand> {
+    AppCommand::try_parse_from(
+      iter::once("").chain(args.iter().map(|arg| arg.value.as_str())),
+    )
+    .map_err(|err| match offending_arg(&err, args) {
+      Some(arg) => CommandSyntaxError::at_arg(
+        err.to_string().trim_end(),
+        message,
+        arg,
+      )
+      .into(),
+      None => anyhow::Error::msg(err),
+    })
+  }
+
+  /// Runs WM commands chained with `;` or `&&` (e.g. `command focus
+  /// --workspace 1 && move --workspace 2`).
+  ///
+  /// A command after `&&` is skipped if the previous command failed or
+  /// was skipped, whereas a command after `;` is always run.
+  fn handle_command_chain(
+    message: &str,
+    segments: &[CommandSegment],
+    wm: &mut WindowManager,
+    config: &mut UserConfig,
+  ) -> anyhow::Result<ClientResponseData> {
+    // Only the first command is prefixed with `command`, which also
+    // holds the subject container for the chain.
+    let (subject_container_id, first_command) =
+      match Self::parse_app_command(message, &segments[0].args)? {
+        AppCommand::Command {
+          subject_container_id,
+          command,
+          ..
+        } => (subject_container_id, command),
+        _ => bail!(
+          "Only WM commands can be chained (e.g. `command focus --workspace 1 && close`)."
+        ),
+      };
+
+    let mut commands = vec![ChainedCommand::from(first_command)];
+
+    for segment in &segments[1..] {
+      commands.push(ChainedCommand {
+        command: InvokeCommand::from_args(message, &segment.args)?,
+        operator: segment.operator,
+      });
+    }
+
+    let command_strs = commands
+      .iter()
+      .map(|chained| chained.command.to_string())
+      .collect::<Vec<_>>();
+
+    let (command_results, subject_container_id) =
+      wm.process_command_batch(commands, subject_container_id, config)?;
+
+    let results = Self::command_results(command_strs, command_results);
+
+    Ok(ClientResponseData::CommandBatch(CommandBatchData {
+      results,
+      subject_container_id,
+    }))
+  }
+
+  /// Pairs each command with its result. Commands without a result
+  /// weren't run because the subject container no longer exists.
+  fn command_results(
+    commands: Vec<String>,
+    results: Vec<anyhow::Result<()>>,
+  ) -> Vec<CommandResult> {
+    let mut results = results.into_iter();
+
+    commands
+      .into_iter()
+      .map(|command| {
+        let error = match results.next() {
+          Some(Ok(())) => None,
+          Some(Err(err)) => Some(err.to_string()),
+          None => Some(
+            "Skipped since the subject container no longer exists."
+              .to_string(),
+          ),
+        };
+
+        CommandResult {
+          command,
+          success: error.is_none(),
+          error,
+        }
+      })
+      .collect()
+  }
+
   fn to_client_response_msg(
-    client_message: String,
+    client_message: ClientMessage,
     response_data: anyhow::Result<ClientResponseData>,
   ) -> anyhow::Result<Message> {
     let error = response_data.as_ref().err().map(|err| err.to_string());
     let success = response_data.as_ref().is_ok();
 
     let message = ServerMessage::ClientResponse(ClientResponseMessage {
-      client_message,
+      client_message: client_message.command,
+      client_message_id: client_message.id,
       data: response_data.ok(),
       error,
       success,
@@ -418,11 +1053,14 @@ impl IpcServer {
   fn to_event_subscription_msg(
     subscription_id: Uuid,
     event: WmEvent,
+    sequence: u64,
   ) -> anyhow::Result<Message> {
     let message =
       ServerMessage::EventSubscription(EventSubscriptionMessage {
         data: Some(event),
         error: None,
+        lagged_count: None,
+        sequence: Some(sequence),
         subscription_id,
         success: true,
       });
@@ -431,7 +1069,32 @@ impl IpcServer {
     Ok(Message::Text(message_json))
   }
 
-  pub fn process_event(&mut self, event: WmEvent) -> anyhow::Result<()> {
+  fn to_event_lagged_msg(
+    subscription_id: Uuid,
+    lagged_count: u64,
+  ) -> anyhow::Result<Message> {
+    let message =
+      ServerMessage::EventSubscription(EventSubscriptionMessage {
+        data: None,
+        error: Some(format!(
+          "Subscription fell behind and {} events were dropped.",
+          lagged_count
+        )),
+        lagged_count: Some(lagged_count),
+        sequence: None,
+        subscription_id,
+        success: false,
+      });
+
+    let message_json = serde_json::to_string(&message)?;
+    Ok(Message::Text(message_json))
+  }
+
+  pub fn process_event(
+    &mut self,
+    event: WmEvent,
+    context: WmEventContext,
+  ) -> anyhow::Result<()> {
     let event_type = match event {
       WmEvent::ApplicationExiting => SubscribableEvent::ApplicationExiting,
       WmEvent::BindingModesChanged { .. } => {
@@ -450,6 +1113,9 @@ impl IpcServer {
       WmEvent::UserConfigChanged { .. } => {
         SubscribableEvent::UserConfigChanged
       }
+      WmEvent::UserConfigReloadFailed { .. } => {
+        SubscribableEvent::UserConfigReloadFailed
+      }
       WmEvent::WindowManaged { .. } => SubscribableEvent::WindowManaged,
       WmEvent::WindowUnmanaged { .. } => {
         SubscribableEvent::WindowUnmanaged
@@ -465,14 +1131,59 @@ impl IpcServer {
       }
     };
 
-    self.event_tx.send((event_type, event))?;
+    self.event_sequence += 1;
+    self.event_tx.send(EmittedEvent {
+      event,
+      event_type,
+      sequence: self.event_sequence,
+      context,
+    })?;
 
     Ok(())
   }
 
+  /// Gracefully shuts down the server.
+  ///
+  /// Pending responses and events are flushed to clients, after which
+  /// their connections are closed with a close frame.
+  pub async fn shutdown(&mut self) {
+    info!("Closing IPC connections.");
+
+    // Stop accepting new connections.
+    for abort_handle in &self.abort_handles {
+      abort_handle.abort();
+    }
+
+    // Drop messages that won't be processed, since they hold on to the
+    // response channel of their connection.
+    self.message_rx.close();
+    while self.message_rx.try_recv().is_ok() {}
+
+    let _ = self.shutdown_tx.send(());
+
+    // Wait for all connections to be closed. The receiver resolves once
+    // every connection has dropped its sender.
+    self.shutdown_complete_tx.take();
+
+    if time::timeout(SHUTDOWN_TIMEOUT, self.shutdown_complete_rx.recv())
+      .await
+      .is_err()
+    {
+      warn!("Timed out waiting for IPC connections to close.");
+    }
+  }
+
   pub fn stop(&self) {
     info!("Shutting down IPC server.");
-    self.abort_handle.abort();
+    for abort_handle in &self.abort_handles {
+      abort_handle.abort();
+    }
+
+    if let Some(token_path) = &self.auth_token_path {
+      if let Err(err) = fs::remove_file(token_path) {
+        warn!("Failed to remove IPC auth token file: {}", err);
+      }
+    }
   }
 }
 
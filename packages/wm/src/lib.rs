@@ -3,9 +3,15 @@
 
 pub mod app_command;
 pub mod cleanup;
+pub mod cli_output;
 pub mod common;
+pub mod completions;
+pub mod config_includes;
+pub mod config_validation;
+pub mod config_variables;
+pub mod config_watcher;
 pub mod containers;
-pub mod ipc_client;
+pub mod ipc_http;
 pub mod ipc_server;
 pub mod monitors;
 pub mod sys_tray;
@@ -5,32 +5,51 @@
 #![feature(iterator_try_collect)]
 #![feature(once_cell_try)]
 
-use std::{env, path::PathBuf};
+use std::{env, path::PathBuf, time::Duration};
 
 use anyhow::{Context, Error, Result};
-use tokio::{process::Command, signal};
+use clap::ValueEnum;
+use tokio::{process::Command, signal, time};
 use tracing::{debug, error, info, warn, Level};
 use tracing_subscriber::{
   fmt::{self, writer::MakeWriterExt},
   layer::SubscriberExt,
 };
+use wm_common::{
+  config_schema, ipc_schema, join_command_args, ClientResponseData,
+  ContainerDto, IpcFeature, IPC_ADDRESS_ENV, IPC_PORT_ENV,
+  IPC_TRANSPORT_ENV,
+};
+use wm_ipc_client::{ConnectionClosed, IpcClient, IpcClientOptions};
 
 use crate::{
-  app_command::{AppCommand, InvokeCommand, Verbosity},
+  app_command::{
+    AppCommand, EventFilterArgs, InvokeCommand, IpcArgs, OutputFormat,
+    SchemaTarget, Verbosity, WaitForEvent,
+  },
+  cli_output::{CliError, CliExitCode, CliOutput},
   common::platform::Platform,
-  ipc_client::IpcClient,
-  ipc_server::{ClientResponseData, IpcServer},
+  completions::print_completions,
+  config_validation::validate_config_path,
+  config_watcher::ConfigWatcher,
+  ipc_server::IpcServer,
   sys_tray::SystemTray,
-  user_config::UserConfig,
+  user_config::{IpcConfig, UserConfig},
   wm::WindowManager,
   wm_event::WmEvent,
 };
 
 mod app_command;
 mod cleanup;
+mod cli_output;
 mod common;
+mod completions;
+mod config_includes;
+mod config_validation;
+mod config_variables;
+mod config_watcher;
 mod containers;
-mod ipc_client;
+mod ipc_http;
 mod ipc_server;
 mod monitors;
 mod sys_tray;
@@ -47,6 +66,8 @@ mod workspaces;
 /// subcommand.
 #[tokio::main]
 async fn main() -> Result<()> {
+  completions::complete_if_requested();
+
   let args = std::env::args().collect::<Vec<_>>();
   let app_command = AppCommand::parse_with_default(&args);
 
@@ -54,8 +75,9 @@ async fn main() -> Result<()> {
     AppCommand::Start {
       config_path,
       verbosity,
+      ipc,
     } => {
-      let res = start_wm(config_path, verbosity).await;
+      let res = start_wm(config_path, verbosity, ipc).await;
 
       // If unable to start the WM, the error is fatal and a message dialog
       // is shown.
@@ -66,13 +88,53 @@ async fn main() -> Result<()> {
 
       res
     }
-    _ => start_cli(args).await,
+    AppCommand::Query {
+      ipc,
+      format,
+      timeout,
+      ..
+    } => run_cli(args, ipc, format, timeout).await,
+    AppCommand::Command { ipc, timeout, .. } => {
+      run_cli(args, ipc, OutputFormat::Json, timeout).await
+    }
+    AppCommand::Sub { ipc, format, .. } => {
+      run_cli(args, ipc, format, None).await
+    }
+    AppCommand::Unsub { ipc, .. } => {
+      run_cli(args, ipc, OutputFormat::Json, None).await
+    }
+    AppCommand::WaitFor {
+      event,
+      filter,
+      timeout,
+      format,
+      command,
+      ipc,
+    } => {
+      match wait_for_event(event, filter, timeout, format, command, ipc)
+        .await
+      {
+        Ok(()) => Ok(()),
+        Err(err) => err.exit(),
+      }
+    }
+    AppCommand::Schema { target } => print_schema(target),
+    AppCommand::Completions { shell } => print_completions(shell),
+    AppCommand::ValidateConfig { config_path } => {
+      let config_path = UserConfig::resolve_path(config_path)?;
+
+      match validate_config_path(&config_path) {
+        Ok(()) => Ok(()),
+        Err(err) => err.exit(),
+      }
+    }
   }
 }
 
 async fn start_wm(
   config_path: Option<PathBuf>,
   verbosity: Verbosity,
+  ipc: IpcArgs,
 ) -> Result<()> {
   let error_log_dir = home::home_dir()
     .context("Unable to get home directory.")?
@@ -100,25 +162,31 @@ async fn start_wm(
     verbosity.level().to_string()
   );
 
-  // Ensure that only one instance of the WM is running.
-  let _single_instance = Platform::new_single_instance()?;
-
   // Parse and validate user config.
   let mut config = UserConfig::new(config_path)?;
 
+  let ipc_config =
+    config.value.general.ipc.clone().with_overrides(&ipc)?;
+
+  // Ensure that only one instance of the WM is running per IPC port.
+  let _single_instance = Platform::new_single_instance(ipc_config.port)?;
+
   // Start watcher process for restoring hidden windows on crash.
-  start_watcher_process()?;
+  start_watcher_process(&ipc_config)?;
 
   // Add application icon to system tray.
   let mut tray = SystemTray::new(&config.path)?;
 
   let mut wm = WindowManager::new(&mut config)?;
 
-  let mut ipc_server = IpcServer::start().await?;
+  let mut ipc_server = IpcServer::start(&ipc_config).await?;
 
   // Start listening for platform events after populating initial state.
   let mut event_listener = Platform::start_event_listener(&config)?;
 
+  // Watch the config files for changes if auto-reload is enabled.
+  let mut config_watcher = ConfigWatcher::start(config.watched_paths());
+
   // Run startup commands.
   let startup_commands = config.value.general.startup_commands.clone();
   wm.process_commands(startup_commands, None, &mut config)?;
@@ -156,7 +224,7 @@ async fn start_wm(
           &mut config,
         )
       },
-      Some(wm_event) = wm.event_rx.recv() => {
+      Some((wm_event, context)) = wm.event_rx.recv() => {
         info!("Received WM event: {:?}", wm_event);
 
         // Update event listener when keyboard or mouse listener needs to
@@ -172,15 +240,48 @@ async fn start_wm(
           );
         }
 
-        ipc_server.process_event(wm_event)
+        // Included files and the auto-reload setting might have changed.
+        if matches!(wm_event, WmEvent::UserConfigChanged { .. }) {
+          config_watcher.update(config.watched_paths());
+        }
+
+        ipc_server.process_event(wm_event, context)
       },
       Some(_) = tray.config_reload_rx.recv() => {
         wm.process_commands(
-          vec![InvokeCommand::WmReloadConfig],
+          vec![InvokeCommand::WmReloadConfig.into()],
           None,
           &mut config,
         ).map(|_| ())
       },
+      Some(_) = config_watcher.change_rx.recv() => {
+        // The config file might be mid-replace or have been deleted, in
+        // which case the previous config is kept.
+        if !config.path.exists() {
+          warn!(
+            "Config file {} doesn't exist. Skipping reload.",
+            config.path.display()
+          );
+
+          continue;
+        }
+
+        info!("Reloading config after file change.");
+
+        // An invalid config is only logged, rather than shown in a dialog,
+        // since the file is likely still being edited. The previous config
+        // stays in place and a `user_config_reload_failed` event is
+        // emitted.
+        if let Err(err) = wm.process_commands(
+          vec![InvokeCommand::WmReloadConfig.into()],
+          None,
+          &mut config,
+        ) {
+          error!("{:?}", err);
+        }
+
+        Ok(())
+      },
     };
 
     if let Err(err) = res {
@@ -196,65 +297,428 @@ async fn start_wm(
   wm.state.emit_event(WmEvent::ApplicationExiting);
 
   // Emit remaining WM events before exiting.
-  while let Ok(wm_event) = wm.event_rx.try_recv() {
+  while let Ok((wm_event, context)) = wm.event_rx.try_recv() {
     info!("Emitting WM event before shutting down: {:?}", wm_event);
 
-    if let Err(err) = ipc_server.process_event(wm_event) {
+    if let Err(err) = ipc_server.process_event(wm_event, context) {
       warn!("{:?}", err);
     }
   }
 
+  // Flush the remaining events to clients and close their connections.
+  ipc_server.shutdown().await;
+
   Ok(())
 }
 
-async fn start_cli(args: Vec<String>) -> Result<()> {
-  let mut client = IpcClient::connect().await?;
+/// Outputs the JSON schema for the given target to stdout.
+fn print_schema(target: SchemaTarget) -> Result<()> {
+  let schema = match target {
+    SchemaTarget::Config => config_schema(),
+    SchemaTarget::Ipc => ipc_schema(),
+  };
 
-  let message = args[1..].join(" ");
-  client
-    .send(&message)
-    .await
-    .context("Failed to send command to IPC server.")?;
+  println!("{}", serde_json::to_string_pretty(&schema)?);
+  Ok(())
+}
+
+/// Runs a CLI command against a running instance of the WM.
+///
+/// Errors are output to stderr and exit the process with a code based on
+/// the type of failure.
+async fn run_cli(
+  args: Vec<String>,
+  ipc: IpcArgs,
+  format: OutputFormat,
+  timeout: Option<Duration>,
+) -> Result<()> {
+  match start_cli(args, ipc, format, timeout).await {
+    Ok(()) => Ok(()),
+    Err(err) => err.exit(),
+  }
+}
 
+async fn start_cli(
+  args: Vec<String>,
+  ipc: IpcArgs,
+  format: OutputFormat,
+  timeout: Option<Duration>,
+) -> Result<(), CliError> {
+  let mut client = connect_cli_client(&ipc, timeout).await?;
+  let mut output = CliOutput::new(format);
+
+  // Table rows show the workspace of each window, which isn't part of
+  // the window itself.
+  if format == OutputFormat::Table {
+    let workspaces = client
+      .query_workspaces()
+      .await
+      .context("Failed to query workspaces from IPC server.")
+      .map_err(CliError::from_request_error)?;
+
+    for workspace in workspaces {
+      output.track_workspace(&workspace);
+    }
+  }
+
+  // Quote arguments that were unquoted by the shell (e.g. workspace
+  // names with spaces), since the IPC server re-parses the message.
+  let message = join_command_args(&args[1..]);
   let client_response = client
-    .client_response(&message)
+    .request(&message)
     .await
-    .context("Failed to receive response from IPC server.")?;
+    .context("Failed to receive response from IPC server.")
+    .map_err(CliError::from_request_error)?;
+
+  if !client_response.success {
+    return Err(CliError::new(
+      CliExitCode::CommandFailed,
+      anyhow::anyhow!(client_response
+        .error
+        .unwrap_or_else(|| "Unknown error.".to_string())),
+    ));
+  }
 
-  match client_response.data {
+  match &client_response.data {
     // For event subscriptions, omit the initial response message and
     // continuously output subsequent event messages.
-    Some(ClientResponseData::EventSubscribe(data)) => loop {
-      let event_subscription = client
-        .event_subscription(&data.subscription_id)
-        .await
-        .context("Failed to receive response from IPC server.")?;
-
-      println!("{}", serde_json::to_string(&event_subscription)?);
-    },
+    Some(ClientResponseData::EventSubscribe(data)) => {
+      if let Some(header) = output.event_header() {
+        println!("{}", header);
+      }
+
+      loop {
+        let event_subscription =
+          match client.next_event_message(&data.subscription_id).await {
+            Ok(event_subscription) => event_subscription,
+            // Exit cleanly when the WM shuts down.
+            Err(err)
+              if err
+                .downcast_ref::<ConnectionClosed>()
+                .is_some_and(ConnectionClosed::is_shutdown) =>
+            {
+              return Ok(());
+            }
+            Err(err) => {
+              return Err(CliError::from_request_error(
+                err.context("Failed to receive response from IPC server."),
+              ))
+            }
+          };
+
+        match &event_subscription.error {
+          Some(err) => eprintln!("error: {}", err),
+          None => {
+            println!("{}", output.format_event(&event_subscription)?)
+          }
+        }
+      }
+    }
     // For all other messages, output and exit when the first response
     // message is received.
+    data => {
+      println!("{}", output.format_response(&client_response)?);
+
+      // Chained commands are output as a whole, but fail if any of the
+      // commands failed.
+      if let Some(ClientResponseData::CommandBatch(batch)) = data {
+        let errors = batch
+          .results
+          .iter()
+          .filter(|result| !result.success)
+          .map(|result| {
+            format!(
+              "{}: {}",
+              result.command,
+              result.error.as_deref().unwrap_or("Unknown error.")
+            )
+          })
+          .collect::<Vec<_>>();
+
+        if !errors.is_empty() {
+          return Err(CliError::new(
+            CliExitCode::CommandFailed,
+            anyhow::anyhow!(errors.join("\n")),
+          ));
+        }
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Connects to a running instance of the WM for a CLI command.
+async fn connect_cli_client(
+  ipc: &IpcArgs,
+  timeout: Option<Duration>,
+) -> Result<IpcClient, CliError> {
+  let ipc_config = UserConfig::client_ipc_config(ipc)?;
+
+  // Reconnecting is pointless for a single request, and would delay the
+  // exit when the WM isn't running.
+  let default_options = IpcClientOptions::default();
+  let options = IpcClientOptions {
+    connect_timeout: timeout.unwrap_or(default_options.connect_timeout),
+    request_timeout: timeout.unwrap_or(default_options.request_timeout),
+    reconnect_attempts: 0,
+    ..default_options
+  };
+
+  IpcClient::connect_with_options(&ipc_config, options)
+    .await
+    .map_err(CliError::from_connect_error)
+}
+
+/// Waits for an event whose container matches the filter, outputs the
+/// container, and optionally runs a command with it as the subject.
+async fn wait_for_event(
+  event: WaitForEvent,
+  filter: EventFilterArgs,
+  timeout: Option<Duration>,
+  format: OutputFormat,
+  command: Vec<String>,
+  ipc: IpcArgs,
+) -> Result<(), CliError> {
+  filter
+    .window
+    .validate()
+    .map_err(|err| CliError::new(CliExitCode::InvalidArgs, err))?;
+
+  let mut client = connect_cli_client(&ipc, None).await?;
+
+  // Older servers resolve the filters from the current state rather than
+  // from when the event was emitted, which can miss matching events.
+  let capabilities = client
+    .capabilities()
+    .await
+    .map_err(CliError::from_request_error)?;
+
+  if !capabilities.has_feature(IpcFeature::WaitForEvents) {
+    return Err(CliError::new(
+      CliExitCode::CommandFailed,
+      anyhow::anyhow!(
+        "IPC server (v{}) doesn't support `wait-for`.",
+        capabilities.version
+      ),
+    ));
+  }
+
+  // Containers of the event are filtered by the IPC server, which knows
+  // their workspace and monitor.
+  let event_name = event
+    .subscribable_event()
+    .to_possible_value()
+    .context("Invalid event.")?
+    .get_name()
+    .to_string();
+
+  let message = join_command_args(
+    &[
+      vec!["sub".to_string(), "-e".to_string(), event_name],
+      filter.to_args(),
+    ]
+    .concat(),
+  );
+
+  let client_response = client
+    .request(&message)
+    .await
+    .context("Failed to subscribe to events.")
+    .map_err(CliError::from_request_error)?;
+
+  let subscription_id = match client_response.data {
+    Some(ClientResponseData::EventSubscribe(data))
+      if client_response.success =>
+    {
+      data.subscription_id
+    }
     _ => {
-      println!("{}", serde_json::to_string(&client_response)?);
+      return Err(CliError::new(
+        CliExitCode::CommandFailed,
+        anyhow::anyhow!(client_response.error.unwrap_or_else(|| {
+          "Failed to subscribe to events.".to_string()
+        })),
+      ))
+    }
+  };
+
+  // Waiting for a specific window also matches an already managed
+  // window, since it might have been managed before the subscription
+  // was set up. Other events are only matched once they occur.
+  let is_existing_matched =
+    event == WaitForEvent::WindowManaged && filter.window.is_set();
+
+  let wait = async {
+    // The current state is checked after subscribing, such that a match
+    // that happens in between isn't missed.
+    if is_existing_matched {
+      if let Some(window) =
+        find_managed_window(&mut client, &filter).await?
+      {
+        return anyhow::Ok(window);
+      }
+    }
+
+    let mut last_sequence = None;
+
+    loop {
+      let event_subscription =
+        client.next_event_message(&subscription_id).await?;
+
+      // Re-check the current state if events were dropped.
+      if event_subscription.lagged_count.is_some() {
+        if is_existing_matched {
+          if let Some(window) =
+            find_managed_window(&mut client, &filter).await?
+          {
+            return anyhow::Ok(window);
+          }
+        }
+
+        continue;
+      }
+
+      // Skip events that have already been seen.
+      if let Some(sequence) = event_subscription.sequence {
+        if last_sequence.is_some_and(|last| sequence <= last) {
+          continue;
+        }
+
+        last_sequence = Some(sequence);
+      }
+
+      let container = event_subscription
+        .data
+        .as_ref()
+        .and_then(|wm_event| wm_event.container());
+
+      if let Some(container) = container
+        .filter(|container| event.is_match(container, &filter.window))
+      {
+        return anyhow::Ok(container.clone());
+      }
+    }
+  };
+
+  let result = match timeout {
+    Some(timeout) => time::timeout(timeout, wait)
+      .await
+      .context("Timed out waiting for event.")
+      .and_then(|result| result),
+    None => wait.await,
+  };
+
+  let container = result.map_err(|err| {
+    match err
+      .downcast_ref::<ConnectionClosed>()
+      .is_some_and(ConnectionClosed::is_shutdown)
+    {
+      true => CliError::new(CliExitCode::NotRunning, err),
+      false => CliError::from_request_error(err),
+    }
+  })?;
+
+  let mut output = CliOutput::new(format);
+  println!("{}", output.format_container(&container)?);
+
+  if !command.is_empty() {
+    let message = format!(
+      "command --id {} {}",
+      container.id(),
+      join_command_args(&command)
+    );
+
+    let client_response = client
+      .request(&message)
+      .await
+      .context("Failed to receive response from IPC server.")
+      .map_err(CliError::from_request_error)?;
+
+    if !client_response.success {
+      return Err(CliError::new(
+        CliExitCode::CommandFailed,
+        anyhow::anyhow!(client_response
+          .error
+          .unwrap_or_else(|| "Unknown error.".to_string())),
+      ));
     }
   }
 
   Ok(())
 }
 
+/// Finds an already managed window that matches the filter.
+async fn find_managed_window(
+  client: &mut IpcClient,
+  filter: &EventFilterArgs,
+) -> anyhow::Result<Option<ContainerDto>> {
+  let monitors = client
+    .query_monitors()
+    .await
+    .context("Failed to query monitors from IPC server.")?;
+
+  for (monitor_index, monitor) in monitors.iter().enumerate() {
+    if filter.monitor.is_some_and(|index| index != monitor_index) {
+      continue;
+    }
+
+    for workspace in &monitor.children {
+      let ContainerDto::Workspace(workspace_dto) = workspace else {
+        continue;
+      };
+
+      if filter
+        .workspace
+        .as_ref()
+        .is_some_and(|name| *name != workspace_dto.name)
+      {
+        continue;
+      }
+
+      let window = workspace
+        .self_and_descendant_windows()
+        .into_iter()
+        .map(|window| ContainerDto::Window(window.clone()))
+        .find(|window| {
+          filter.container_id.is_none_or(|id| id == window.id())
+            && WaitForEvent::WindowManaged.is_match(window, &filter.window)
+        });
+
+      if window.is_some() {
+        return Ok(window);
+      }
+    }
+  }
+
+  Ok(None)
+}
+
 /// Launches watcher binary. This is a separate process that is responsible
 /// for restoring hidden windows in case the main WM process crashes.
 ///
 /// This assumes the watcher binary exists in the same directory as the WM
-/// binary.
-fn start_watcher_process() -> anyhow::Result<tokio::process::Child, Error>
-{
+/// binary. The IPC address is passed via environment variables, so that
+/// the watcher connects to the same instance.
+fn start_watcher_process(
+  ipc_config: &IpcConfig,
+) -> anyhow::Result<tokio::process::Child, Error> {
   let watcher_path = env::current_exe()?
     .parent()
     .context("Failed to resolve path to the watcher process.")?
     .join("glazewm-watcher");
 
   Command::new(&watcher_path)
+    .env(IPC_ADDRESS_ENV, &ipc_config.address)
+    .env(IPC_PORT_ENV, ipc_config.port.to_string())
+    .env(
+      IPC_TRANSPORT_ENV,
+      ipc_config
+        .transport
+        .to_possible_value()
+        .context("Invalid IPC transport.")?
+        .get_name(),
+    )
     .spawn()
     .context("Failed to start watcher process.")
 }
@@ -8,7 +8,7 @@ use crate::{
   },
   monitors::Monitor,
   user_config::UserConfig,
-  wm_event::WmEvent,
+  wm_event::{WmEvent, WmEventContext},
   wm_state::WmState,
   workspaces::commands::sort_workspaces,
 };
@@ -55,12 +55,17 @@ pub fn remove_monitor(
     });
   }
 
+  let event_context = WmEventContext::new_removed(&monitor.clone().into());
+
   detach_container(monitor.clone().into())?;
 
-  state.emit_event(WmEvent::MonitorRemoved {
-    removed_id: monitor.id(),
-    removed_device_name: monitor.native().device_name()?.to_string(),
-  });
+  state.emit_event_with_context(
+    WmEvent::MonitorRemoved {
+      removed_id: monitor.id(),
+      removed_device_name: monitor.native().device_name()?.to_string(),
+    },
+    event_context,
+  );
 
   Ok(())
 }
@@ -2,3 +2,4 @@ pub mod commands;
 mod monitor;
 
 pub use monitor::*;
+pub use wm_common::MonitorDto;
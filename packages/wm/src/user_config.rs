@@ -1,11 +1,22 @@
 use std::{collections::HashMap, env, fs, path::PathBuf};
 
 use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
+pub use wm_common::{
+  BindingModeConfig, BorderEffectConfig, CornerEffectConfig, CornerStyle,
+  CursorJumpConfig, CursorJumpTrigger, FloatingStateConfig,
+  FullscreenStateConfig, GapsConfig, GeneralConfig,
+  HideTitleBarEffectConfig, InitialWindowState, IpcConfig, IpcTransport,
+  KeybindingConfig, MatchType, MonitorMatchConfig, MonitorSelector,
+  ParsedConfig, WindowBehaviorConfig, WindowEffectConfig,
+  WindowEffectsConfig, WindowMatchConfig, WindowRuleConfig,
+  WindowRuleEvent, WindowStateDefaultsConfig, WorkspaceConfig,
+};
+use wm_common::{InvokeCommand, IpcArgs};
 
 use crate::{
-  app_command::InvokeCommand,
-  common::{Color, LengthValue, RectDelta},
+  config_includes::{
+    merge_config_files, read_config_files, ConfigFileError,
+  },
   containers::{traits::CommonGetters, WindowContainer},
   monitors::Monitor,
   windows::traits::WindowGetters,
@@ -24,9 +35,18 @@ pub struct UserConfig {
   /// Parsed user config value.
   pub value: ParsedConfig,
 
-  /// Unparsed user config string.
+  /// Unparsed user config string. Doesn't include the contents of
+  /// included files.
   pub value_str: String,
 
+  /// Paths of the config files included by the user config file (via
+  /// `include`). Each file is followed by the files that it includes.
+  pub included_paths: Vec<PathBuf>,
+
+  /// Directories that globs under `include` match files in. These are
+  /// watched for added or removed files when auto-reload is enabled.
+  pub include_glob_dirs: Vec<PathBuf>,
+
   /// Hashmap of window rule event types (e.g. `WindowRuleEvent::Manage`)
   /// and the corresponding window rules of that type.
   window_rules_by_event: HashMap<WindowRuleEvent, Vec<WindowRuleConfig>>,
@@ -38,15 +58,14 @@ impl UserConfig {
   ///
   /// Creates a new config file from sample if it doesn't exist.
   pub fn new(config_path: Option<PathBuf>) -> anyhow::Result<Self> {
-    let default_config_path = home::home_dir()
-      .context("Unable to get home directory.")?
-      .join(".glzr/glazewm/config.yaml");
+    let config_path = Self::resolve_path(config_path)?;
 
-    let config_path = config_path
-      .or_else(|| env::var("GLAZEWM_CONFIG_PATH").ok().map(PathBuf::from))
-      .unwrap_or(default_config_path);
+    if !config_path.exists() {
+      Self::create_sample(config_path.clone())?;
+    }
 
-    let (config_value, config_str) = Self::read(&config_path)?;
+    let (config_value, config_str, included_paths, include_glob_dirs) =
+      Self::read(&config_path)?;
 
     let window_rules_by_event = Self::window_rules_by_event(&config_value);
 
@@ -54,28 +73,114 @@ impl UserConfig {
       path: config_path,
       value: config_value,
       value_str: config_str,
+      included_paths,
+      include_glob_dirs,
       window_rules_by_event,
     })
   }
 
-  /// Reads and validates the user config from the given path.
+  /// Gets the path to the user config file.
   ///
-  /// Creates a new config file from sample if it doesn't exist.
+  /// Falls back to the `GLAZEWM_CONFIG_PATH` environment variable, and
+  /// then to `%userprofile%/.glzr/glazewm/config.yaml`.
+  pub fn resolve_path(
+    config_path: Option<PathBuf>,
+  ) -> anyhow::Result<PathBuf> {
+    let default_config_path = home::home_dir()
+      .context("Unable to get home directory.")?
+      .join(".glzr/glazewm/config.yaml");
+
+    Ok(
+      config_path
+        .or_else(|| {
+          env::var("GLAZEWM_CONFIG_PATH").ok().map(PathBuf::from)
+        })
+        .unwrap_or(default_config_path),
+    )
+  }
+
+  /// Reads the parsed config from the default path, along with any files
+  /// that it includes. Returns `None` if it doesn't exist or is invalid.
+  ///
+  /// Used by CLI commands, which don't load the config otherwise.
+  pub fn read_default() -> Option<ParsedConfig> {
+    let config_path = Self::resolve_path(None).ok()?;
+    let config_files = read_config_files(&config_path).ok()?;
+    merge_config_files(&config_files).ok()
+  }
+
+  /// Gets the IPC config for connecting to a running instance.
+  ///
+  /// Uses `general.ipc` from the config file, or the defaults if the
+  /// config file can't be read. Environment variables and the given CLI
+  /// flags are then applied on top.
+  pub fn client_ipc_config(args: &IpcArgs) -> anyhow::Result<IpcConfig> {
+    Self::read_default()
+      .map(|config| config.general.ipc)
+      .unwrap_or_default()
+      .with_overrides(args)
+  }
+
+  /// Reads and validates the user config from the given path, merging in
+  /// any included files.
+  ///
+  /// Returns the parsed config, the unparsed main config file, the paths
+  /// of the included files, and the directories of include globs.
   fn read(
     config_path: &PathBuf,
-  ) -> anyhow::Result<(ParsedConfig, String)> {
-    if !config_path.exists() {
-      Self::create_sample(config_path.clone())?;
-    }
+  ) -> anyhow::Result<(ParsedConfig, String, Vec<PathBuf>, Vec<PathBuf>)>
+  {
+    let mut files =
+      read_config_files(config_path).map_err(Self::with_validate_hint)?;
+
+    let config_value =
+      merge_config_files(&files).map_err(Self::with_validate_hint)?;
+
+    let include_glob_dirs = files
+      .iter()
+      .flat_map(|file| file.glob_dirs.iter().cloned())
+      .collect();
 
-    let config_str = fs::read_to_string(config_path)
-      .context("Unable to read config file.")?;
+    let config_str = files.remove(0).source;
+    let included_paths = files.into_iter().map(|file| file.path).collect();
+
+    Ok((config_value, config_str, included_paths, include_glob_dirs))
+  }
+
+  /// Paths of the config file and of the files that it includes.
+  pub fn file_paths(&self) -> Vec<PathBuf> {
+    std::iter::once(self.path.clone())
+      .chain(self.included_paths.iter().cloned())
+      .collect()
+  }
 
-    // TODO: Improve error formatting of serde_yaml errors. Something
-    // similar to https://github.com/AlexanderThaller/format_serde_error
-    let config_value = serde_yaml::from_str(&config_str)?;
+  /// Paths to watch for automatically reloading the config. Empty if
+  /// auto-reload is disabled.
+  ///
+  /// Directories of include globs are watched as well, such that added
+  /// or removed files are picked up. Subdirectories matched by `**` are
+  /// not watched.
+  pub fn watched_paths(&self) -> Vec<PathBuf> {
+    match self.value.general.auto_reload_config {
+      true => self
+        .file_paths()
+        .into_iter()
+        .chain(self.include_glob_dirs.iter().cloned())
+        .collect(),
+      false => Vec::new(),
+    }
+  }
 
-    Ok((config_value, config_str))
+  /// Suggests running `validate-config` for errors within config files,
+  /// since only the first error is reported.
+  fn with_validate_hint(err: anyhow::Error) -> anyhow::Error {
+    match err.downcast_ref::<ConfigFileError>() {
+      Some(file_err) => anyhow::anyhow!(
+        "{}\n\nRun `glazewm validate-config` for all errors.",
+        file_err
+      ),
+      None => err,
+    }
   }
 
   /// Initializes a new config file from the sample config resource.
@@ -95,12 +200,17 @@ impl UserConfig {
   }
 
   pub fn reload(&mut self) -> anyhow::Result<()> {
-    let (config_value, config_str) = Self::read(&self.path)?;
+    // Included files are re-read as well, since they might have changed
+    // or been added (e.g. when matched by a glob).
+    let (config_value, config_str, included_paths, include_glob_dirs) =
+      Self::read(&self.path)?;
 
     self.window_rules_by_event =
       Self::window_rules_by_event(&config_value);
     self.value = config_value;
     self.value_str = config_str;
+    self.included_paths = included_paths;
+    self.include_glob_dirs = include_glob_dirs;
 
     Ok(())
   }
@@ -118,7 +228,8 @@ impl UserConfig {
       commands: vec![InvokeCommand::SetFloating {
         centered: Some(floating_defaults.centered),
         shown_on_top: Some(floating_defaults.shown_on_top),
-      }],
+      }
+      .into()],
       match_window: vec![
         WindowMatchConfig {
           window_class: Some(MatchType::Equals { equals:
@@ -141,7 +252,7 @@ impl UserConfig {
 
     // Default ignore rules.
     window_rules.push(WindowRuleConfig {
-      commands: vec![InvokeCommand::Ignore],
+      commands: vec![InvokeCommand::Ignore.into()],
       match_window: vec![
         WindowMatchConfig {
           window_process: Some(MatchType::Equals {
@@ -238,25 +349,11 @@ impl UserConfig {
 
         // Check if the window matches the rule.
         rule.match_window.iter().any(|match_config| {
-          let is_process_match = match_config
-            .window_process
-            .as_ref()
-            .map(|match_type| match_type.is_match(&window_process))
-            .unwrap_or(true);
-
-          let is_class_match = match_config
-            .window_class
-            .as_ref()
-            .map(|match_type| match_type.is_match(&window_class))
-            .unwrap_or(true);
-
-          let is_title_match = match_config
-            .window_title
-            .as_ref()
-            .map(|match_type| match_type.is_match(&window_title))
-            .unwrap_or(true);
-
-          is_process_match && is_class_match && is_title_match
+          match_config.is_match(
+            &window_process,
+            &window_class,
+            &window_title,
+          )
         })
       })
       .cloned()
@@ -281,23 +378,118 @@ impl UserConfig {
       .collect()
   }
 
+  /// Gaps for the given workspace, with the workspace's overrides
+  /// applied to the global gaps.
+  pub fn workspace_gaps_config(
+    &self,
+    workspace_config: &WorkspaceConfig,
+  ) -> GapsConfig {
+    let gaps = &workspace_config.gaps;
+
+    GapsConfig {
+      inner_gap: gaps
+        .inner_gap
+        .clone()
+        .unwrap_or_else(|| self.value.gaps.inner_gap.clone()),
+      outer_gap: gaps
+        .outer_gap
+        .clone()
+        .unwrap_or_else(|| self.value.gaps.outer_gap.clone()),
+      ..self.value.gaps.clone()
+    }
+  }
+
+  /// Whether focus follows the cursor for windows on the given
+  /// workspace.
+  pub fn focus_follows_cursor(
+    &self,
+    workspace_config: &WorkspaceConfig,
+  ) -> bool {
+    workspace_config
+      .focus_follows_cursor
+      .unwrap_or(self.value.general.focus_follows_cursor)
+  }
+
+  /// Whether focus follows the cursor on any workspace, in which case
+  /// mouse movement needs to be listened to.
+  pub fn has_focus_follows_cursor(&self) -> bool {
+    self.value.general.focus_follows_cursor
+      || self
+        .value
+        .workspaces
+        .iter()
+        .any(|config| config.focus_follows_cursor == Some(true))
+  }
+
+  /// Gets the first inactive workspace config that is bound to the given
+  /// monitor.
   pub fn workspace_config_for_monitor(
     &self,
     monitor: &Monitor,
+    monitors: &[Monitor],
     active_workspaces: &[Workspace],
   ) -> Option<&WorkspaceConfig> {
     let inactive_configs =
       self.inactive_workspace_configs(active_workspaces);
 
     inactive_configs.into_iter().find(|&config| {
-      config
-        .bind_to_monitor
-        .as_ref()
-        .map(|monitor_index| monitor.index() == *monitor_index as usize)
-        .unwrap_or(false)
+      Self::bound_monitor(config, monitors)
+        .is_some_and(|bound_monitor| bound_monitor.id() == monitor.id())
     })
   }
 
+  /// Gets the connected monitor that the workspace is bound to via
+  /// `bind_to_monitor`.
+  pub fn bound_monitor(
+    workspace_config: &WorkspaceConfig,
+    monitors: &[Monitor],
+  ) -> Option<Monitor> {
+    workspace_config
+      .bind_to_monitor
+      .as_ref()
+      .and_then(|selector| Self::resolve_monitor(selector, monitors))
+  }
+
+  fn resolve_monitor(
+    selector: &MonitorSelector,
+    monitors: &[Monitor],
+  ) -> Option<Monitor> {
+    match selector {
+      MonitorSelector::Index(index) => monitors
+        .iter()
+        .find(|monitor| monitor.index() == *index as usize)
+        .cloned(),
+      MonitorSelector::Match(match_config) => monitors
+        .iter()
+        .find(|monitor| Self::is_monitor_match(monitor, match_config))
+        .cloned(),
+      MonitorSelector::Fallback(selectors) => selectors
+        .iter()
+        .find_map(|selector| Self::resolve_monitor(selector, monitors)),
+    }
+  }
+
+  fn is_monitor_match(
+    monitor: &Monitor,
+    match_config: &MonitorMatchConfig,
+  ) -> bool {
+    let native_monitor = monitor.native();
+
+    match_config.is_match(
+      native_monitor.device_name().ok().map(String::as_str),
+      native_monitor
+        .device_path()
+        .ok()
+        .flatten()
+        .map(String::as_str),
+      native_monitor
+        .hardware_id()
+        .ok()
+        .flatten()
+        .map(String::as_str),
+    )
+  }
+
   /// Gets the first inactive workspace config, prioritizing configs that
   /// don't have a monitor binding.
   pub fn next_inactive_workspace_config(
@@ -331,331 +523,3 @@ impl UserConfig {
     });
   }
 }
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct ParsedConfig {
-  pub binding_modes: Vec<BindingModeConfig>,
-  pub gaps: GapsConfig,
-  pub general: GeneralConfig,
-  pub keybindings: Vec<KeybindingConfig>,
-  pub window_behavior: WindowBehaviorConfig,
-  pub window_effects: WindowEffectsConfig,
-  pub window_rules: Vec<WindowRuleConfig>,
-  pub workspaces: Vec<WorkspaceConfig>,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct BindingModeConfig {
-  /// Name of the binding mode.
-  pub name: String,
-
-  /// Display name of the binding mode.
-  pub display_name: Option<String>,
-
-  /// Keybindings that will be active when the binding mode is active.
-  pub keybindings: Vec<KeybindingConfig>,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct GapsConfig {
-  /// Whether to scale the gaps with the DPI of the monitor.
-  #[serde(default = "default_bool::<true>")]
-  pub scale_with_dpi: bool,
-
-  /// Gap between adjacent windows.
-  pub inner_gap: LengthValue,
-
-  /// Gap between windows and the screen edge.
-  pub outer_gap: RectDelta,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct GeneralConfig {
-  /// Config for automatically moving the cursor.
-  pub cursor_jump: CursorJumpConfig,
-
-  /// Whether to automatically focus windows underneath the cursor.
-  #[serde(default = "default_bool::<false>")]
-  pub focus_follows_cursor: bool,
-
-  /// Whether to switch back and forth between the previously focused
-  /// workspace when focusing the current workspace.
-  #[serde(default = "default_bool::<true>")]
-  pub toggle_workspace_on_refocus: bool,
-
-  /// Commands to run when the WM has started (e.g. to run a script or
-  /// launch another application).
-  #[serde(default)]
-  pub startup_commands: Vec<InvokeCommand>,
-
-  /// Commands to run just before the WM is shutdown.
-  #[serde(default)]
-  pub shutdown_commands: Vec<InvokeCommand>,
-
-  /// Commands to run after the WM config has reloaded.
-  #[serde(default)]
-  pub config_reload_commands: Vec<InvokeCommand>,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct CursorJumpConfig {
-  /// Whether to automatically move the cursor on the specified trigger.
-  #[serde(default = "default_bool::<true>")]
-  pub enabled: bool,
-
-  /// Trigger for cursor jump.
-  #[serde(default)]
-  pub trigger: CursorJumpTrigger,
-}
-
-#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(rename_all = "snake_case")]
-pub enum CursorJumpTrigger {
-  #[default]
-  MonitorFocus,
-  WindowFocus,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct KeybindingConfig {
-  /// Keyboard shortcut to trigger the keybinding.
-  pub bindings: Vec<String>,
-
-  /// WM commands to run when the keybinding is triggered.
-  pub commands: Vec<InvokeCommand>,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct WindowBehaviorConfig {
-  /// New windows are created in this state whenever possible.
-  #[serde(default)]
-  pub initial_state: InitialWindowState,
-
-  /// Sets the default options for when a new window is created. This also
-  /// changes the defaults for when the state change commands, like
-  /// `set_floating`, are used without any flags.
-  pub state_defaults: WindowStateDefaultsConfig,
-}
-
-#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(rename_all = "snake_case")]
-pub enum InitialWindowState {
-  #[default]
-  Tiling,
-  Floating,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct WindowStateDefaultsConfig {
-  pub floating: FloatingStateConfig,
-  pub fullscreen: FullscreenStateConfig,
-}
-
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct FloatingStateConfig {
-  /// Whether to center new floating windows.
-  #[serde(default = "default_bool::<true>")]
-  pub centered: bool,
-
-  /// Whether to show floating windows as always on top.
-  #[serde(default = "default_bool::<false>")]
-  pub shown_on_top: bool,
-}
-
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct FullscreenStateConfig {
-  /// Whether to prefer fullscreen windows to be maximized.
-  #[serde(default = "default_bool::<true>")]
-  pub maximized: bool,
-
-  /// Whether to show fullscreen windows as always on top.
-  #[serde(default = "default_bool::<false>")]
-  pub shown_on_top: bool,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct WindowEffectsConfig {
-  /// Visual effects to apply to the focused window.
-  pub focused_window: WindowEffectConfig,
-
-  /// Visual effects to apply to non-focused windows.
-  pub other_windows: WindowEffectConfig,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct WindowEffectConfig {
-  /// Config for optionally applying a colored border.
-  pub border: BorderEffectConfig,
-
-  /// Config for optionally hiding the title bar.
-  #[serde(default)]
-  pub hide_title_bar: HideTitleBarEffectConfig,
-
-  /// Config for optionally changing the corner style.
-  #[serde(default)]
-  pub corner_style: CornerEffectConfig,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct BorderEffectConfig {
-  /// Whether to enable the effect.
-  #[serde(default = "default_bool::<false>")]
-  pub enabled: bool,
-
-  /// Color of the window border.
-  #[serde(default = "default_blue")]
-  pub color: Color,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize, Default)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct HideTitleBarEffectConfig {
-  /// Whether to enable the effect.
-  #[serde(default = "default_bool::<false>")]
-  pub enabled: bool,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct CornerEffectConfig {
-  /// Whether to enable the effect.
-  #[serde(default = "default_bool::<false>")]
-  pub enabled: bool,
-
-  /// Style of the window corners.
-  #[serde(default)]
-  pub style: CornerStyle,
-}
-
-#[derive(
-  Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize, Default,
-)]
-#[serde(rename_all = "snake_case")]
-pub enum CornerStyle {
-  #[default]
-  Default,
-  Square,
-  Rounded,
-  SmallRounded,
-}
-
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct WindowRuleConfig {
-  pub commands: Vec<InvokeCommand>,
-
-  #[serde(rename = "match")]
-  pub match_window: Vec<WindowMatchConfig>,
-
-  #[serde(default = "default_window_rule_on")]
-  pub on: Vec<WindowRuleEvent>,
-
-  #[serde(default = "default_bool::<true>")]
-  pub run_once: bool,
-}
-
-#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct WindowMatchConfig {
-  #[serde(default)]
-  pub window_process: Option<MatchType>,
-
-  #[serde(default)]
-  pub window_class: Option<MatchType>,
-
-  #[serde(default)]
-  pub window_title: Option<MatchType>,
-}
-
-/// Due to limitations in `serde_yaml`, we need to use an untagged enum
-/// instead of a regular enum for serialization. Using a regular enum
-/// causes issues with flow-style objects in YAML.
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-#[serde(untagged)]
-pub enum MatchType {
-  Equals { equals: String },
-  Includes { includes: String },
-  Regex { regex: String },
-  NotEquals { not_equals: String },
-  NotRegex { not_regex: String },
-}
-
-impl MatchType {
-  /// Whether the given value is a match for the match type.
-  fn is_match(&self, value: &str) -> bool {
-    match self {
-      MatchType::Equals { equals } => value == equals,
-      MatchType::Includes { includes } => value.contains(includes),
-      MatchType::Regex { regex } => regex::Regex::new(regex)
-        .map(|re| re.is_match(value))
-        .unwrap_or(false),
-      MatchType::NotEquals { not_equals } => value != not_equals,
-      MatchType::NotRegex { not_regex } => regex::Regex::new(not_regex)
-        .map(|re| !re.is_match(value))
-        .unwrap_or(false),
-    }
-  }
-}
-
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
-#[serde(rename_all = "snake_case")]
-pub enum WindowRuleEvent {
-  /// When a window receives native focus.
-  Focus,
-  /// When a window is initially managed.
-  Manage,
-  /// When the title of a window changes.
-  TitleChange,
-}
-
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-#[serde(rename_all(serialize = "camelCase"))]
-pub struct WorkspaceConfig {
-  pub name: String,
-  pub display_name: Option<String>,
-  pub bind_to_monitor: Option<u32>,
-  #[serde(default = "default_bool::<false>")]
-  pub keep_alive: bool,
-}
-
-/// Helper function for setting a default value for a boolean field.
-const fn default_bool<const V: bool>() -> bool {
-  V
-}
-
-/// Helper function for setting a default value for a color field.
-const fn default_blue() -> Color {
-  Color {
-    r: 140,
-    g: 190,
-    b: 255,
-    a: 255,
-  }
-}
-
-/// Helper function for setting a default value for window rule events.
-fn default_window_rule_on() -> Vec<WindowRuleEvent> {
-  vec![WindowRuleEvent::Manage, WindowRuleEvent::TitleChange]
-}
-
-impl Default for CornerEffectConfig {
-  fn default() -> Self {
-    CornerEffectConfig {
-      enabled: false,
-      style: CornerStyle::Default,
-    }
-  }
-}
@@ -17,6 +17,7 @@ use crate::{
   },
   wm_event::WmEvent,
   wm_state::WmState,
+  workspaces::Workspace,
 };
 
 pub fn manage_window(
@@ -132,7 +133,12 @@ fn create_window(
 
   let gaps_config = config.value.gaps.clone();
   let window_state =
-    window_state_to_create(&native_window, &nearest_monitor, config)?;
+    window_state_to_create(
+      &native_window,
+      &nearest_monitor,
+      &target_workspace,
+      config,
+    )?;
 
   let window_container: WindowContainer = match window_state {
     WindowState::Tiling => TilingWindow::new(
@@ -183,6 +189,7 @@ fn create_window(
 fn window_state_to_create(
   native_window: &NativeWindow,
   nearest_monitor: &Monitor,
+  target_workspace: &Workspace,
   config: &UserConfig,
 ) -> anyhow::Result<WindowState> {
   if native_window.is_minimized()? {
@@ -207,7 +214,10 @@ fn window_state_to_create(
     ));
   }
 
-  Ok(WindowState::default_from_config(config))
+  Ok(WindowState::default_for_workspace(
+    &config.value,
+    &target_workspace.config(),
+  ))
 }
 
 fn insertion_target(
@@ -1,6 +1,7 @@
 use tracing::info;
 
 use crate::{
+  app_command::InvokeCommandExt,
   containers::{traits::CommonGetters, WindowContainer},
   user_config::{UserConfig, WindowRuleEvent},
   windows::traits::WindowGetters,
@@ -23,8 +24,24 @@ pub fn run_window_rules(
   for rule in pending_window_rules {
     info!("Running window rule with commands: {:?}.", rule.commands);
 
-    for command in &rule.commands {
-      command.run(subject_window.clone().into(), state, config)?;
+    let mut is_prev_success = true;
+    let mut first_error = None;
+
+    for chained in &rule.commands {
+      if !chained.should_run(is_prev_success) {
+        continue;
+      }
+
+      let result =
+        chained
+          .command
+          .run(subject_window.clone().into(), state, config);
+
+      is_prev_success = result.is_ok();
+
+      if let Err(err) = result {
+        first_error.get_or_insert(err);
+      }
 
       // Update the subject container in case the container type changes.
       // For example, when going from a tiling to a floating window.
@@ -37,6 +54,10 @@ pub fn run_window_rules(
       }
     }
 
+    if let Some(err) = first_error {
+      return Err(err);
+    }
+
     // Add the window rule as done.
     if rule.run_once {
       let window_rules = subject_window
@@ -10,7 +10,7 @@ use crate::{
     WindowContainer,
   },
   windows::{traits::WindowGetters, WindowState},
-  wm_event::WmEvent,
+  wm_event::{WmEvent, WmEventContext},
   wm_state::WmState,
 };
 
@@ -24,6 +24,10 @@ pub fn unmanage_window(
   // Get container to switch focus to after the window has been removed.
   let focus_target = state.focus_target_after_removal(&window.clone());
 
+  // Capture the window's details for the event, since it's no longer
+  // retrievable from the tree once detached.
+  let event_context = WmEventContext::new_removed(&window.clone().into());
+
   detach_container(window.clone().into())?;
 
   // After detaching the container, flatten any redundant split containers.
@@ -33,10 +37,13 @@ pub fn unmanage_window(
     flatten_child_split_containers(ancestor.clone())?;
   }
 
-  state.emit_event(WmEvent::WindowUnmanaged {
-    unmanaged_id: window.id(),
-    unmanaged_handle: window.native().handle,
-  });
+  state.emit_event_with_context(
+    WmEvent::WindowUnmanaged {
+      unmanaged_id: window.id(),
+      unmanaged_handle: window.native().handle,
+    },
+    event_context,
+  );
 
   // Reassign focus to suitable target.
   if let Some(focus_target) = focus_target {
@@ -1,13 +1,10 @@
-mod active_drag;
 pub mod commands;
 mod non_tiling_window;
 mod tiling_window;
 pub mod traits;
-mod window_dto;
-mod window_state;
 
-pub use active_drag::*;
 pub use non_tiling_window::*;
 pub use tiling_window::*;
-pub use window_dto::*;
-pub use window_state::*;
+pub use wm_common::{
+  ActiveDrag, ActiveDragOperation, WindowDto, WindowState,
+};
@@ -26,7 +26,7 @@ use crate::{
   impl_position_getters_as_resizable, impl_tiling_size_getters,
   impl_window_getters,
   user_config::{GapsConfig, WindowRuleConfig},
-  windows::active_drag::ActiveDrag,
+  windows::ActiveDrag,
 };
 
 #[derive(Clone)]
@@ -7,7 +7,7 @@ use crate::{
     platform::NativeWindow, DisplayState, LengthValue, Rect, RectDelta,
   },
   user_config::{UserConfig, WindowRuleConfig},
-  windows::{active_drag::ActiveDrag, WindowState},
+  windows::{ActiveDrag, WindowState},
 };
 
 #[delegatable_trait]
@@ -37,7 +37,7 @@ pub trait WindowGetters {
     let possible_states = [
       Some(target_state),
       self.prev_state(),
-      Some(WindowState::default_from_config(config)),
+      Some(WindowState::default_from_config(&config.value)),
     ];
 
     // Return the first possible state with a different discriminant.
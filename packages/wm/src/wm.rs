@@ -1,9 +1,10 @@
 use anyhow::Context;
 use tokio::sync::mpsc::{self};
 use uuid::Uuid;
+use wm_common::ChainedCommand;
 
 use crate::{
-  app_command::InvokeCommand,
+  app_command::{InvokeCommand, InvokeCommandExt},
   common::{
     commands::platform_sync,
     events::{
@@ -17,13 +18,14 @@ use crate::{
     },
     platform::PlatformEvent,
   },
+  containers::Container,
   user_config::UserConfig,
-  wm_event::WmEvent,
+  wm_event::{WmEvent, WmEventContext},
   wm_state::WmState,
 };
 
 pub struct WindowManager {
-  pub event_rx: mpsc::UnboundedReceiver<WmEvent>,
+  pub event_rx: mpsc::UnboundedReceiver<(WmEvent, WmEventContext)>,
   pub exit_rx: mpsc::UnboundedReceiver<()>,
   pub state: WmState,
 }
@@ -102,22 +104,14 @@ impl WindowManager {
 
   pub fn process_commands(
     &mut self,
-    commands: Vec<InvokeCommand>,
+    commands: Vec<ChainedCommand>,
     subject_container_id: Option<Uuid>,
     config: &mut UserConfig,
   ) -> anyhow::Result<Uuid> {
+    let subject_container =
+      self.subject_container(subject_container_id)?;
     let state = &mut self.state;
 
-    // Get the container to run WM commands with.
-    let subject_container = match subject_container_id {
-      Some(id) => state.container_by_id(id).with_context(|| {
-        format!("No container found with the given ID '{}'.", id)
-      })?,
-      None => state
-        .focused_container()
-        .context("No subject container for command.")?,
-    };
-
     let new_subject_container_id = InvokeCommand::run_multiple(
       commands,
       subject_container,
@@ -129,4 +123,46 @@ impl WindowManager {
 
     Ok(new_subject_container_id)
   }
+
+  /// Runs a batch of WM commands with a single platform sync at the end.
+  ///
+  /// Unlike `process_commands`, the result of each command is returned
+  /// individually. Commands chained with `&&` after a failing command
+  /// are skipped.
+  pub fn process_command_batch(
+    &mut self,
+    commands: Vec<ChainedCommand>,
+    subject_container_id: Option<Uuid>,
+    config: &mut UserConfig,
+  ) -> anyhow::Result<(Vec<anyhow::Result<()>>, Uuid)> {
+    let subject_container =
+      self.subject_container(subject_container_id)?;
+    let state = &mut self.state;
+
+    let (results, new_subject_container_id) =
+      InvokeCommand::run_batch(commands, subject_container, state, config);
+
+    // Sync regardless of failures, since commands prior to the failing
+    // one may have changed the state.
+    platform_sync(state, config)?;
+
+    Ok((results, new_subject_container_id))
+  }
+
+  /// Gets the container to run WM commands with. Defaults to the focused
+  /// container if no ID is given.
+  fn subject_container(
+    &self,
+    subject_container_id: Option<Uuid>,
+  ) -> anyhow::Result<Container> {
+    match subject_container_id {
+      Some(id) => self.state.container_by_id(id).with_context(|| {
+        format!("No container found with the given ID '{}'.", id)
+      }),
+      None => self
+        .state
+        .focused_container()
+        .context("No subject container for command."),
+    }
+  }
 }
@@ -1,63 +1,42 @@
-use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+pub use wm_common::WmEvent;
 
-use crate::{
-  common::TilingDirection,
-  containers::ContainerDto,
-  user_config::{BindingModeConfig, ParsedConfig},
-};
+use crate::containers::{traits::CommonGetters, Container, ContainerDto};
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(
-  tag = "eventType",
-  rename_all = "snake_case",
-  rename_all_fields = "camelCase"
-)]
-pub enum WmEvent {
-  ApplicationExiting,
-  BindingModesChanged {
-    new_binding_modes: Vec<BindingModeConfig>,
-  },
-  FocusChanged {
-    focused_container: ContainerDto,
-  },
-  FocusedContainerMoved {
-    focused_container: ContainerDto,
-  },
-  MonitorAdded {
-    added_monitor: ContainerDto,
-  },
-  MonitorRemoved {
-    removed_id: Uuid,
-    removed_device_name: String,
-  },
-  MonitorUpdated {
-    updated_monitor: ContainerDto,
-  },
-  TilingDirectionChanged {
-    direction_container: ContainerDto,
-    new_tiling_direction: TilingDirection,
-  },
-  UserConfigChanged {
-    config_path: String,
-    config_string: String,
-    parsed_config: ParsedConfig,
-  },
-  WindowManaged {
-    managed_window: ContainerDto,
-  },
-  WindowUnmanaged {
-    unmanaged_id: Uuid,
-    unmanaged_handle: isize,
-  },
-  WorkspaceActivated {
-    activated_workspace: ContainerDto,
-  },
-  WorkspaceDeactivated {
-    deactivated_id: Uuid,
-    deactivated_name: String,
-  },
-  WorkspaceUpdated {
-    updated_workspace: ContainerDto,
-  },
+/// Details of the container that a WM event is about, as of when the
+/// event was emitted. Used for filtering event subscriptions.
+///
+/// These are captured on emit, since the container might have moved or
+/// been removed by the time that the event reaches subscribers.
+#[derive(Clone, Debug, Default)]
+pub struct WmEventContext {
+  /// Name of the workspace that the event's container belongs to.
+  pub workspace_name: Option<String>,
+
+  /// Index of the monitor that the event's container belongs to.
+  pub monitor_index: Option<usize>,
+
+  /// Snapshot of a removed container. Events for removed containers
+  /// (e.g. `WindowUnmanaged`) only carry the container's ID.
+  pub removed_container: Option<ContainerDto>,
+}
+
+impl WmEventContext {
+  /// Captures the workspace and monitor of the given container.
+  pub fn new(container: &Container) -> Self {
+    Self {
+      workspace_name: container
+        .workspace()
+        .map(|workspace| workspace.config().name),
+      monitor_index: container.monitor().map(|monitor| monitor.index()),
+      removed_container: None,
+    }
+  }
+
+  /// Captures the given container before it's removed from the tree.
+  pub fn new_removed(container: &Container) -> Self {
+    Self {
+      removed_container: container.to_dto().ok(),
+      ..Self::new(container)
+    }
+  }
 }
@@ -20,7 +20,7 @@ use crate::{
   monitors::{commands::add_monitor, Monitor},
   user_config::{BindingModeConfig, UserConfig},
   windows::{commands::manage_window, traits::WindowGetters, WindowState},
-  wm_event::WmEvent,
+  wm_event::{WmEvent, WmEventContext},
   workspaces::{Workspace, WorkspaceTarget},
 };
 
@@ -58,7 +58,7 @@ pub struct WmState {
   has_initialized: bool,
 
   /// Sender for emitting WM-related events.
-  event_tx: mpsc::UnboundedSender<WmEvent>,
+  event_tx: mpsc::UnboundedSender<(WmEvent, WmEventContext)>,
 
   /// Sender for gracefully shutting down the WM.
   exit_tx: mpsc::UnboundedSender<()>,
@@ -82,7 +82,7 @@ pub struct PendingSync {
 
 impl WmState {
   pub fn new(
-    event_tx: mpsc::UnboundedSender<WmEvent>,
+    event_tx: mpsc::UnboundedSender<(WmEvent, WmEventContext)>,
     exit_tx: mpsc::UnboundedSender<()>,
   ) -> Self {
     Self {
@@ -430,8 +430,24 @@ impl WmState {
   /// is to prevent events (e.g. workspace activation events) from being
   /// emitted via IPC server before the initial state is prepared.
   pub fn emit_event(&self, event: WmEvent) {
+    let context = event
+      .container_id()
+      .and_then(|id| self.container_by_id(id))
+      .map(|container| WmEventContext::new(&container))
+      .unwrap_or_default();
+
+    self.emit_event_with_context(event, context);
+  }
+
+  /// Emits a WM event for a container that has already been removed
+  /// from the tree, using a context captured prior to its removal.
+  pub fn emit_event_with_context(
+    &self,
+    event: WmEvent,
+    context: WmEventContext,
+  ) {
     if self.has_initialized {
-      if let Err(err) = self.event_tx.send(event) {
+      if let Err(err) = self.event_tx.send((event, context)) {
         warn!("Failed to send event: {}", err);
       }
     }
@@ -36,14 +36,7 @@ pub fn activate_workspace(
 
   let target_monitor = target_monitor
     .or_else(|| {
-      workspace_config
-        .bind_to_monitor
-        .and_then(|index| {
-          state
-            .monitors()
-            .into_iter()
-            .find(|monitor| monitor.index() == index as usize)
-        })
+      UserConfig::bound_monitor(&workspace_config, &state.monitors())
         .or_else(|| {
           state
             .focused_container()
@@ -52,16 +45,21 @@ pub fn activate_workspace(
     })
     .context("Failed to get a target monitor for the workspace.")?;
 
-  let monitor_rect = target_monitor.to_rect()?;
-  let tiling_direction = match monitor_rect.height() > monitor_rect.width()
-  {
-    true => TilingDirection::Vertical,
-    false => TilingDirection::Horizontal,
+  let tiling_direction = match workspace_config.tiling_direction.clone() {
+    Some(tiling_direction) => tiling_direction,
+    None => {
+      let monitor_rect = target_monitor.to_rect()?;
+
+      match monitor_rect.height() > monitor_rect.width() {
+        true => TilingDirection::Vertical,
+        false => TilingDirection::Horizontal,
+      }
+    }
   };
 
   let workspace = Workspace::new(
     workspace_config.clone(),
-    config.value.gaps.clone(),
+    config.workspace_gaps_config(&workspace_config),
     tiling_direction,
   );
 
@@ -103,6 +101,7 @@ fn workspace_config(
       .and_then(|target_monitor| {
         config.workspace_config_for_monitor(
           &target_monitor,
+          &state.monitors(),
           &state.workspaces(),
         )
       })
@@ -1,6 +1,6 @@
 use crate::{
   containers::{commands::detach_container, traits::CommonGetters},
-  wm_event::WmEvent,
+  wm_event::{WmEvent, WmEventContext},
   wm_state::WmState,
   workspaces::Workspace,
 };
@@ -11,12 +11,18 @@ pub fn deactivate_workspace(
   workspace: Workspace,
   state: &WmState,
 ) -> anyhow::Result<()> {
+  let event_context =
+    WmEventContext::new_removed(&workspace.clone().into());
+
   detach_container(workspace.clone().into())?;
 
-  state.emit_event(WmEvent::WorkspaceDeactivated {
-    deactivated_id: workspace.id(),
-    deactivated_name: workspace.config().name,
-  });
+  state.emit_event_with_context(
+    WmEvent::WorkspaceDeactivated {
+      deactivated_id: workspace.id(),
+      deactivated_name: workspace.config().name,
+    },
+    event_context,
+  );
 
   Ok(())
 }
@@ -2,5 +2,6 @@ pub mod commands;
 mod workspace;
 mod workspace_target;
 
+pub use wm_common::WorkspaceDto;
 pub use workspace::*;
 pub use workspace_target::*;
@@ -5,8 +5,8 @@ use std::{
 };
 
 use anyhow::Context;
-use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use wm_common::WorkspaceDto;
 
 use crate::{
   common::{Rect, TilingDirection},
@@ -34,27 +34,6 @@ struct WorkspaceInner {
   tiling_direction: TilingDirection,
 }
 
-/// User-friendly representation of a workspace.
-///
-/// Used for IPC and debug logging.
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct WorkspaceDto {
-  id: Uuid,
-  name: String,
-  display_name: Option<String>,
-  parent_id: Option<Uuid>,
-  children: Vec<ContainerDto>,
-  child_focus_order: Vec<Uuid>,
-  has_focus: bool,
-  is_displayed: bool,
-  width: i32,
-  height: i32,
-  x: i32,
-  y: i32,
-  tiling_direction: TilingDirection,
-}
-
 impl Workspace {
   pub fn new(
     config: WorkspaceConfig,
@@ -106,6 +85,12 @@ impl Workspace {
       .unwrap_or(false)
   }
 
+  /// Gaps config for the workspace, which also applies to the
+  /// containers within it.
+  pub fn gaps_config(&self) -> GapsConfig {
+    self.0.borrow().gaps_config.clone()
+  }
+
   pub fn set_gaps_config(&self, gaps_config: GapsConfig) {
     self.0.borrow_mut().gaps_config = gaps_config;
   }